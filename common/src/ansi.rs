@@ -0,0 +1,190 @@
+use crate::charcanvas::CharCanvas;
+use crate::grid2d::Grid2d;
+use crate::image::Rgb;
+use std::fmt::Write as _;
+use std::io::{Stdout, Write};
+use std::time::Duration;
+
+pub const CURSOR_HOME: &str = "\x1b[H";
+pub const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+pub const CLEAR_LINE: &str = "\x1b[K";
+pub const CLEAR_BELOW: &str = "\x1b[J";
+pub const HIDE_CURSOR: &str = "\x1b[?25l";
+pub const SHOW_CURSOR: &str = "\x1b[?25h";
+pub const REVERSE: &str = "\x1b[7m";
+pub const RESET: &str = "\x1b[0m";
+
+/// The escape sequence switching the text color to `color`.
+pub fn foreground(color: Rgb) -> String {
+    let [r, g, b] = color;
+    format!("\x1b[38;2;{};{};{}m", r, g, b)
+}
+
+/// Shows a grid in a terminal while a simulation changes it, redrawing it in place instead of
+/// scrolling. A disabled live view ignores every draw, so simulations can draw unconditionally.
+pub struct LiveView<W: Write = Stdout> {
+    out: Option<W>,
+    delay: Duration,
+    interval: usize,
+    until_next: usize,
+    drawn: bool,
+}
+
+impl<W: Write> Default for LiveView<W> {
+    fn default() -> Self {
+        Self {
+            out: None,
+            delay: Duration::ZERO,
+            interval: 1,
+            until_next: 0,
+            drawn: false,
+        }
+    }
+}
+
+impl LiveView {
+    pub const FLAG: &'static str = "--live";
+
+    /// A live view on stdout if the program was started with `--live`, and a disabled one
+    /// otherwise.
+    pub fn from_args() -> Self {
+        if std::env::args().any(|arg| arg == Self::FLAG) {
+            Self::new(std::io::stdout())
+        } else {
+            Self::default()
+        }
+    }
+}
+
+impl<W: Write> LiveView<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out: Some(out),
+            ..Self::default()
+        }
+    }
+
+    /// Waits this long after each frame, so fast simulations can be followed.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Only draws one out of every `interval` frames.
+    pub fn every(mut self, interval: usize) -> Self {
+        assert!(interval > 0);
+        self.interval = interval;
+        self
+    }
+
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.out.is_some()
+    }
+
+    pub fn draw(&mut self, canvas: &CharCanvas, color: impl Fn(char) -> Option<Rgb>) {
+        self.draw_with(canvas, |c| *c, |c| color(*c))
+    }
+
+    /// Draws every tile as the character `tile` picks, in the color `color` picks or in the
+    /// terminal's default color for `None`.
+    pub fn draw_with<T>(
+        &mut self,
+        grid: &Grid2d<T>,
+        tile: impl Fn(&T) -> char,
+        color: impl Fn(&T) -> Option<Rgb>,
+    ) {
+        let Some(out) = &mut self.out else {
+            return;
+        };
+        if self.until_next > 0 {
+            self.until_next -= 1;
+            return;
+        }
+        self.until_next = self.interval - 1;
+
+        let mut frame = String::new();
+        if self.drawn {
+            frame += CURSOR_HOME;
+        } else {
+            frame += HIDE_CURSOR;
+            frame += CLEAR_SCREEN;
+        }
+        for row in grid.rows() {
+            let mut current = None;
+            for t in row {
+                let next = color(t);
+                if next != current {
+                    match next {
+                        Some(rgb) => frame += &foreground(rgb),
+                        None => frame += RESET,
+                    }
+                    current = next;
+                }
+                frame.push(tile(t));
+            }
+            if current.is_some() {
+                frame += RESET;
+            }
+            writeln!(frame, "{}", CLEAR_LINE).unwrap();
+        }
+        frame += CLEAR_BELOW;
+
+        out.write_all(frame.as_bytes())
+            .and_then(|_| out.flush())
+            .expect("failed to draw live view");
+        self.drawn = true;
+        std::thread::sleep(self.delay);
+    }
+}
+
+impl<W: Write> Drop for LiveView<W> {
+    fn drop(&mut self) {
+        if let (Some(out), true) = (&mut self.out, self.drawn) {
+            let _ = out.write_all(SHOW_CURSOR.as_bytes());
+            let _ = out.flush();
+        }
+    }
+}
+
+#[test]
+fn test_live_view() {
+    use crate::vec2i::Point;
+
+    let mut out = vec![];
+    {
+        let mut live = LiveView::new(&mut out);
+        let mut canvas = CharCanvas::with_size('.', [3, 1]);
+        let color = |c| (c == '#').then_some([255, 0, 0]);
+        live.draw(&canvas, color);
+        canvas[Point::new(1, 0)] = '#';
+        live.draw(&canvas, color);
+    }
+    assert_eq!(
+        "\x1b[?25l\x1b[H\x1b[2J...\x1b[K\n\x1b[J\
+         \x1b[H.\x1b[38;2;255;0;0m#\x1b[0m.\x1b[K\n\x1b[J\
+         \x1b[?25h",
+        String::from_utf8(out).unwrap()
+    );
+}
+
+#[test]
+fn test_live_view_interval() {
+    let mut out = vec![];
+    {
+        let mut live = LiveView::new(&mut out).every(2);
+        let canvas = CharCanvas::with_size('.', [1, 1]);
+        for _ in 0..3 {
+            live.draw(&canvas, |_| None);
+        }
+    }
+    let frames = String::from_utf8(out).unwrap();
+    assert_eq!(2, frames.matches(CLEAR_BELOW).count());
+}
+
+#[test]
+fn test_live_view_disabled() {
+    let mut live = LiveView::<Vec<u8>>::default();
+    assert!(!live.is_enabled());
+    live.draw(&CharCanvas::new('.'), |_| None);
+}
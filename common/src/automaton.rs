@@ -0,0 +1,233 @@
+#[cfg(test)]
+use crate::charcanvas::CharCanvas;
+use crate::cycle::Cycle;
+use crate::grid2d::Grid2d;
+use crate::gridview::GridView;
+use crate::vec2i::{Bounds, Point};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A cell and the eight cells around it, as seen by an [`Automaton`] rule.
+pub struct Neighborhood<'a, T> {
+    center: Point,
+    tile: &'a T,
+    view: GridView<'a, T>,
+}
+
+impl<'a, T> Neighborhood<'a, T> {
+    #[inline]
+    pub fn center(&self) -> Point {
+        self.center
+    }
+
+    #[inline]
+    pub fn tile(&self) -> &'a T {
+        self.tile
+    }
+
+    pub fn neighbors(&self) -> impl Iterator<Item = &'a T> + '_ {
+        self.view
+            .iter()
+            .filter(|(p, _)| *p != self.center)
+            .map(|(_, tile)| tile)
+    }
+
+    pub fn count(&self, predicate: impl Fn(&T) -> bool) -> usize {
+        self.neighbors().filter(|tile| predicate(tile)).count()
+    }
+
+    #[inline]
+    pub fn view(&self) -> &GridView<'a, T> {
+        &self.view
+    }
+}
+
+/// Steps every cell of a grid at once with a rule over each cell's [`Neighborhood`], swapping
+/// between two buffers so no grid is allocated per step.
+pub struct Automaton<T> {
+    current: Grid2d<T>,
+    next: Grid2d<T>,
+    steps: usize,
+    growing: bool,
+}
+
+impl<T: Copy + PartialEq> Automaton<T> {
+    pub fn new(grid: Grid2d<T>) -> Self {
+        let next = Grid2d::with_bounds(grid.empty, grid.bounds);
+        Self {
+            current: grid,
+            next,
+            steps: 0,
+            growing: false,
+        }
+    }
+
+    /// Grows the grid by one tile on every side before each step where a non-empty tile is at
+    /// the edge, so rules that spread into empty space never run out of grid.
+    pub fn growing(mut self) -> Self {
+        self.growing = true;
+        self
+    }
+
+    #[inline]
+    pub fn grid(&self) -> &Grid2d<T> {
+        &self.current
+    }
+
+    #[inline]
+    pub fn into_grid(self) -> Grid2d<T> {
+        self.current
+    }
+
+    #[inline]
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    fn touches_edge(&self) -> bool {
+        let grid = &self.current;
+        let empty = grid.empty;
+        let is_set = |tile: &T| *tile != empty;
+        let mut rows = grid.rows();
+        let mut columns = grid.columns();
+        let edge_rows = rows.next().into_iter().chain(rows.next_back());
+        let edge_columns = columns.next().into_iter().chain(columns.next_back());
+        edge_rows.flatten().any(is_set) || edge_columns.flatten().any(is_set)
+    }
+
+    fn grow(&mut self) {
+        if self.touches_edge() {
+            let grown = self.current.bounds.expand(1);
+            self.current.extend_to_bounds(&grown);
+        }
+        if self.next.bounds != self.current.bounds {
+            self.next = Grid2d::with_bounds(self.current.empty, self.current.bounds);
+        }
+    }
+
+    /// Applies `rule` to every cell. Returns whether any cell changed.
+    pub fn step(&mut self, rule: impl Fn(&Neighborhood<T>) -> T) -> bool {
+        if self.growing {
+            self.grow();
+        }
+        let current = &self.current;
+        let mut changed = false;
+        for ((center, tile), (_, next)) in current.iter().zip(self.next.iter_mut()) {
+            let neighborhood = Neighborhood {
+                center,
+                tile,
+                view: current.view(Bounds::point(center).expand(1)),
+            };
+            *next = rule(&neighborhood);
+            changed |= *next != *tile;
+        }
+        std::mem::swap(&mut self.current, &mut self.next);
+        self.steps += 1;
+        changed
+    }
+
+    pub fn run(&mut self, steps: usize, rule: impl Fn(&Neighborhood<T>) -> T) {
+        for _ in 0..steps {
+            self.step(&rule);
+        }
+    }
+
+    /// Steps until a step changes nothing, and returns the step counter including that last
+    /// step. Never returns for a grid that oscillates, like the blinker; see
+    /// [`Automaton::run_until_cycle`] for those.
+    pub fn run_until_stable(&mut self, rule: impl Fn(&Neighborhood<T>) -> T) -> usize {
+        while self.step(&rule) {}
+        self.steps
+    }
+}
+
+impl<T: Copy + Eq + Hash> Automaton<T> {
+    /// Steps until the grid is the same as at an earlier step, and returns that cycle. A grid
+    /// that stops changing is a cycle of length 1, an oscillator one of its period. Every grid
+    /// is kept until then, and a pattern that moves, like a glider, never repeats.
+    pub fn run_until_cycle(&mut self, rule: impl Fn(&Neighborhood<T>) -> T) -> Cycle {
+        let mut seen = HashMap::new();
+        loop {
+            if let Some(&start) = seen.get(&self.current) {
+                return Cycle {
+                    start,
+                    length: self.steps - start,
+                };
+            }
+            seen.insert(self.current.clone(), self.steps);
+            self.step(&rule);
+        }
+    }
+}
+
+#[cfg(test)]
+fn life(neighborhood: &Neighborhood<char>) -> char {
+    match (neighborhood.tile(), neighborhood.count(|t| *t == '#')) {
+        ('#', 2..=3) | (_, 3) => '#',
+        _ => '.',
+    }
+}
+
+#[test]
+fn test_automaton_blinker() {
+    let mut automaton = Automaton::new(CharCanvas::from_text(
+        '.',
+        [0, 0],
+        ".....\n..#..\n..#..\n..#..\n.....\n",
+    ));
+    assert!(automaton.step(life));
+    assert_eq!(
+        ".....\n.....\n.###.\n.....\n.....\n",
+        automaton.grid().to_string()
+    );
+    automaton.run(3, life);
+    assert_eq!(4, automaton.steps());
+    assert_eq!(
+        ".....\n..#..\n..#..\n..#..\n.....\n",
+        automaton.grid().to_string()
+    );
+    assert_eq!(
+        Cycle {
+            start: 4,
+            length: 2
+        },
+        automaton.run_until_cycle(life)
+    );
+    assert_eq!(6, automaton.steps());
+}
+
+#[test]
+fn test_automaton_growing() {
+    let mut automaton =
+        Automaton::new(CharCanvas::from_text('.', [0, 0], ".#.\n..#\n###\n")).growing();
+    automaton.run(4, life);
+    let grid = automaton.into_grid();
+    assert_eq!(
+        vec![
+            Point::new(2, 1),
+            Point::new(3, 2),
+            Point::new(1, 3),
+            Point::new(2, 3),
+            Point::new(3, 3)
+        ],
+        grid.points_where(|t| *t == '#').collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_automaton_fixed_point() {
+    let mut automaton = Automaton::new(CharCanvas::from_text(
+        '.',
+        [0, 0],
+        "....\n.##.\n.#..\n....\n",
+    ));
+    assert_eq!(2, automaton.run_until_stable(life));
+    assert_eq!("....\n.##.\n.##.\n....\n", automaton.grid().to_string());
+    assert_eq!(
+        Cycle {
+            start: 2,
+            length: 1
+        },
+        automaton.run_until_cycle(life)
+    );
+}
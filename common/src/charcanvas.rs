@@ -1,3 +1,33 @@
 use crate::grid2d::Grid2d;
+use crate::vec2i::{Bounds, Point};
+use std::iter::repeat;
 
 pub type CharCanvas = Grid2d<char>;
+
+impl Grid2d<char> {
+    /// A canvas of the lines of `text`, with the first character at `top_left`. Lines shorter
+    /// than the longest one are padded with `empty`.
+    pub fn from_text(empty: char, top_left: impl Into<Point>, text: &str) -> Self {
+        let lines = text.lines().collect::<Vec<_>>();
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut tiles = Vec::with_capacity(width * lines.len());
+        for line in &lines {
+            tiles.extend(line.chars().chain(repeat(empty)).take(width));
+        }
+        let size = [width as i32, lines.len() as i32];
+        Grid2d::from_parts(empty, Bounds::with_top_left_and_size(top_left, size), tiles)
+    }
+}
+
+#[test]
+fn test_canvas_from_text() {
+    let canvas = CharCanvas::from_text('.', [-1, 2], "ab\nc\n");
+    assert_eq!(Bounds::new(2, 0, 3, -1), canvas.bounds);
+    assert_eq!('c', canvas[Point::new(-1, 3)]);
+    assert_eq!("ab\nc.\n", canvas.to_string());
+    assert!(CharCanvas::from_text('.', [0, 0], "").bounds.is_empty());
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A sequence of states that repeats from step `start` on, every `length` steps.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step with the same state as step `n`.
+    #[inline]
+    pub const fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+
+    /// The value at step `n`, for a value that changes by the same amount every cycle, like a
+    /// height or a score. `values[i]` is the value at step `i`, and must cover at least steps
+    /// `0..=start + length`.
+    pub fn extrapolate(&self, n: usize, values: &[i64]) -> i64 {
+        debug_assert!(values.len() > self.start + self.length);
+        if n < values.len() {
+            return values[n];
+        }
+        let per_cycle = values[self.start + self.length] - values[self.start];
+        let cycles = ((n - self.start) / self.length) as i64;
+        values[self.equivalent_step(n)] + cycles * per_cycle
+    }
+}
+
+/// Finds the cycle with Brent's algorithm, keeping only a couple of states in memory. The step
+/// function is run from `initial` more than once, so it must not have side effects.
+pub fn brent<S: Clone, K: PartialEq>(
+    initial: S,
+    step: impl Fn(&S) -> S,
+    key: impl Fn(&S) -> K,
+) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = key(&initial);
+    let mut hare = step(&initial);
+    while tortoise != key(&hare) {
+        if power == length {
+            tortoise = key(&hare);
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = (0..length).fold(initial, |state, _| step(&state));
+    let mut start = 0;
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Finds the cycle by remembering the step at which each state key was first seen. Returns the
+/// cycle along with the states from step 0 up to and including `start + length`, so values for
+/// [`Cycle::extrapolate`] can be derived from them.
+pub fn memoize<S, K: Hash + Eq>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    key: impl Fn(&S) -> K,
+) -> (Cycle, Vec<S>) {
+    let mut seen = HashMap::new();
+    let mut states = vec![initial];
+    loop {
+        let n = states.len() - 1;
+        let state = &states[n];
+        if let Some(start) = seen.insert(key(state), n) {
+            let cycle = Cycle {
+                start,
+                length: n - start,
+            };
+            return (cycle, states);
+        }
+        let next = step(state);
+        states.push(next);
+    }
+}
+
+#[cfg(test)]
+fn pseudo_random(x: &u32) -> u32 {
+    (x * x + 1) % 255
+}
+
+#[test]
+fn test_brent() {
+    let cycle = brent(3u32, pseudo_random, |x| *x);
+    let (memoized, states) = memoize(3u32, pseudo_random, |x| *x);
+    assert_eq!(memoized, cycle);
+    assert_eq!(cycle.start + cycle.length + 1, states.len());
+    assert_eq!(states[cycle.start], states[cycle.start + cycle.length]);
+    assert!(states[..cycle.start + cycle.length]
+        .iter()
+        .enumerate()
+        .all(|(i, s)| !states[..i].contains(s)));
+}
+
+#[test]
+fn test_cycle_from_start() {
+    let cycle = brent(0, |x| (x + 1) % 5, |x| *x);
+    assert_eq!(
+        Cycle {
+            start: 0,
+            length: 5
+        },
+        cycle
+    );
+    assert_eq!(cycle, memoize(0, |x| (x + 1) % 5, |x| *x).0);
+    assert_eq!(2, cycle.equivalent_step(1_000_000_000_002));
+}
+
+#[test]
+fn test_cycle_extrapolate() {
+    // Walks 10, 11, 12, 13, 14, 15, 11, 12, ... while summing the positions.
+    let step = |(position, total): &(u32, i64)| {
+        let next = if *position == 15 { 11 } else { position + 1 };
+        (next, total + next as i64)
+    };
+    let (cycle, states) = memoize((10, 0), step, |(position, _)| *position);
+    assert_eq!(
+        Cycle {
+            start: 1,
+            length: 5
+        },
+        cycle
+    );
+    let values = states.iter().map(|(_, total)| *total).collect::<Vec<_>>();
+
+    let naive = (0..1000).fold((10, 0), |state, _| step(&state));
+    assert_eq!(naive.1, cycle.extrapolate(1000, &values));
+    assert_eq!(values[3], cycle.extrapolate(3, &values));
+    assert_eq!(cycle, brent((10, 0), step, |(position, _)| *position));
+}
@@ -0,0 +1,169 @@
+use crate::rotation::Rotate45;
+use crate::vec2i::{
+    Bounds, Point, DOWN, DOWN_LEFT, DOWN_RIGHT, LEFT, RIGHT, UP, UP_LEFT, UP_RIGHT,
+};
+use std::ops::RangeInclusive;
+
+/// All points within a Manhattan distance `radius` of `center`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Diamond {
+    center: Point,
+    radius: i32,
+}
+
+impl Diamond {
+    #[inline]
+    pub const fn new(center: Point, radius: i32) -> Self {
+        debug_assert!(radius >= 0);
+        Self { center, radius }
+    }
+
+    #[inline]
+    pub fn with_center_and_point(center: Point, p: &Point) -> Self {
+        Self::new(center, center.manhattan_distance(p))
+    }
+
+    #[inline]
+    pub const fn center(&self) -> Point {
+        self.center
+    }
+
+    #[inline]
+    pub const fn radius(&self) -> i32 {
+        self.radius
+    }
+
+    #[inline]
+    pub fn contains(&self, p: &Point) -> bool {
+        self.center.manhattan_distance(p) <= self.radius
+    }
+
+    #[inline]
+    pub fn contains_diamond(&self, other: &Diamond) -> bool {
+        self.center.manhattan_distance(&other.center) + other.radius <= self.radius
+    }
+
+    #[inline]
+    pub fn overlaps(&self, other: &Diamond) -> bool {
+        self.center.manhattan_distance(&other.center) <= self.radius + other.radius
+    }
+
+    #[inline]
+    fn slice(&self, center: i32, distance: i32) -> Option<RangeInclusive<i32>> {
+        let half_width = self.radius - distance.abs();
+        (half_width >= 0).then(|| center - half_width..=center + half_width)
+    }
+
+    #[inline]
+    pub fn row(&self, y: i32) -> Option<RangeInclusive<i32>> {
+        self.slice(self.center.x(), y - self.center.y())
+    }
+
+    #[inline]
+    pub fn column(&self, x: i32) -> Option<RangeInclusive<i32>> {
+        self.slice(self.center.y(), x - self.center.x())
+    }
+
+    #[inline]
+    pub fn bounds(&self) -> Bounds {
+        Bounds::from_points(
+            self.center + UP_LEFT * self.radius,
+            self.center + DOWN_RIGHT * self.radius,
+        )
+    }
+
+    /// The diamond as a square in the coordinates of [`Rotate45`].
+    #[inline]
+    pub const fn rotated_bounds(&self) -> Bounds {
+        Rotate45.diamond_to_square(self)
+    }
+
+    /// The points at distance `radius + 1` from the center, just outside the diamond, clockwise
+    /// from the top.
+    pub fn outer_perimeter(&self) -> impl Iterator<Item = Point> {
+        let center = self.center;
+        let distance = self.radius + 1;
+        [
+            (UP, DOWN_RIGHT),
+            (RIGHT, DOWN_LEFT),
+            (DOWN, UP_LEFT),
+            (LEFT, UP_RIGHT),
+        ]
+        .into_iter()
+        .flat_map(move |(vertex, step)| {
+            let start = center + vertex * distance;
+            (0..distance).map(move |i| start + step * i)
+        })
+    }
+}
+
+#[test]
+fn test_diamond_contains() {
+    let diamond = Diamond::new(Point::new(1, -1), 2);
+    assert!(diamond.contains(&Point::new(1, -1)));
+    assert!(diamond.contains(&Point::new(3, -1)));
+    assert!(diamond.contains(&Point::new(2, 0)));
+    assert!(!diamond.contains(&Point::new(3, 0)));
+    assert!(!diamond.contains(&Point::new(1, 2)));
+    assert_eq!(
+        13,
+        diamond
+            .bounds()
+            .iter_points()
+            .filter(|p| diamond.contains(p))
+            .count()
+    );
+}
+
+#[test]
+fn test_diamond_slices() {
+    let diamond = Diamond::with_center_and_point(Point::new(8, 7), &Point::new(2, 10));
+    assert_eq!(9, diamond.radius());
+    assert_eq!(Some(-1..=17), diamond.row(7));
+    assert_eq!(Some(8..=8), diamond.row(16));
+    assert_eq!(Some(6..=10), diamond.row(0));
+    assert_eq!(None, diamond.row(17));
+    assert_eq!(Some(5..=9), diamond.column(1));
+    assert_eq!(None, diamond.column(-2));
+}
+
+#[test]
+fn test_diamond_overlaps() {
+    let a = Diamond::new(Point::new(0, 0), 2);
+    assert!(a.overlaps(&Diamond::new(Point::new(3, 2), 3)));
+    assert!(a.overlaps(&Diamond::new(Point::new(0, -4), 2)));
+    assert!(!a.overlaps(&Diamond::new(Point::new(3, 2), 2)));
+    assert!(a.contains_diamond(&Diamond::new(Point::new(1, 0), 1)));
+    assert!(!a.contains_diamond(&Diamond::new(Point::new(1, 0), 2)));
+}
+
+#[test]
+fn test_diamond_outer_perimeter() {
+    let diamond = Diamond::new(Point::new(5, 5), 1);
+    let perimeter = diamond.outer_perimeter().collect::<Vec<_>>();
+    assert_eq!(8, perimeter.len());
+    assert_eq!(Point::new(5, 3), perimeter[0]);
+    assert_eq!(Point::new(6, 4), perimeter[1]);
+    assert_eq!(Point::new(7, 5), perimeter[2]);
+    assert!(perimeter
+        .iter()
+        .all(|p| diamond.center().manhattan_distance(p) == 2));
+    assert_eq!(
+        4,
+        Diamond::new(Point::new(0, 0), 0).outer_perimeter().count()
+    );
+}
+
+#[test]
+fn test_diamond_rotated_bounds() {
+    let diamond = Diamond::new(Point::new(3, 1), 2);
+    let rotated = diamond.rotated_bounds();
+    assert_eq!(Bounds::new(0, 6, 4, 2), rotated);
+    for p in diamond.bounds().iter_points() {
+        let [x, y]: [i32; 2] = p.into();
+        assert_eq!(
+            diamond.contains(&p),
+            rotated.contains(&Point::new(x + y, x - y))
+        );
+    }
+}
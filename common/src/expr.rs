@@ -0,0 +1,509 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::num::Wrapping;
+use std::str::{CharIndices, FromStr};
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl BinOp {
+    #[inline]
+    const fn precedence(&self) -> u8 {
+        match self {
+            BinOp::Add | BinOp::Sub => 1,
+            BinOp::Mul | BinOp::Div | BinOp::Rem => 2,
+        }
+    }
+
+    const fn symbol(&self) -> char {
+        match self {
+            BinOp::Add => '+',
+            BinOp::Sub => '-',
+            BinOp::Mul => '*',
+            BinOp::Div => '/',
+            BinOp::Rem => '%',
+        }
+    }
+
+    fn from_symbol(c: char) -> Option<BinOp> {
+        [BinOp::Add, BinOp::Sub, BinOp::Mul, BinOp::Div, BinOp::Rem]
+            .into_iter()
+            .find(|op| op.symbol() == c)
+    }
+}
+
+/// An integer arithmetic expression over named variables.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Expr {
+    Const(i64),
+    Var(String),
+    Neg(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    /// The byte offset in the source where parsing failed.
+    pub position: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EvalError {
+    UnknownVariable(String),
+    DivisionByZero,
+    Overflow,
+    /// The operation has no result in the value type, like division by a number with no
+    /// modular inverse.
+    Undefined(BinOp),
+}
+
+impl Display for EvalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalError::UnknownVariable(name) => write!(f, "unknown variable {}", name),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+            EvalError::Overflow => write!(f, "arithmetic overflow"),
+            EvalError::Undefined(op) => write!(f, "{} is undefined here", op.symbol()),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// A number type expressions can be evaluated in.
+pub trait Value: Copy {
+    /// What it takes to turn a constant into a value, like a modulus.
+    type Context;
+
+    fn constant(c: i64, context: &Self::Context) -> Result<Self, EvalError>;
+    fn negate(self) -> Result<Self, EvalError>;
+    fn apply(op: BinOp, a: Self, b: Self) -> Result<Self, EvalError>;
+}
+
+/// Checked arithmetic, failing instead of wrapping around.
+impl Value for i64 {
+    type Context = ();
+
+    #[inline]
+    fn constant(c: i64, _: &()) -> Result<Self, EvalError> {
+        Ok(c)
+    }
+
+    #[inline]
+    fn negate(self) -> Result<Self, EvalError> {
+        self.checked_neg().ok_or(EvalError::Overflow)
+    }
+
+    fn apply(op: BinOp, a: Self, b: Self) -> Result<Self, EvalError> {
+        if b == 0 && matches!(op, BinOp::Div | BinOp::Rem) {
+            return Err(EvalError::DivisionByZero);
+        }
+        match op {
+            BinOp::Add => a.checked_add(b),
+            BinOp::Sub => a.checked_sub(b),
+            BinOp::Mul => a.checked_mul(b),
+            BinOp::Div => a.checked_div(b),
+            BinOp::Rem => a.checked_rem(b),
+        }
+        .ok_or(EvalError::Overflow)
+    }
+}
+
+/// Arithmetic that wraps around on overflow, for when speed matters more than noticing it.
+impl Value for Wrapping<i64> {
+    type Context = ();
+
+    #[inline]
+    fn constant(c: i64, _: &()) -> Result<Self, EvalError> {
+        Ok(Wrapping(c))
+    }
+
+    #[inline]
+    fn negate(self) -> Result<Self, EvalError> {
+        Ok(-self)
+    }
+
+    fn apply(op: BinOp, a: Self, b: Self) -> Result<Self, EvalError> {
+        if b.0 == 0 && matches!(op, BinOp::Div | BinOp::Rem) {
+            return Err(EvalError::DivisionByZero);
+        }
+        Ok(match op {
+            BinOp::Add => a + b,
+            BinOp::Sub => a - b,
+            BinOp::Mul => a * b,
+            BinOp::Div => a / b,
+            BinOp::Rem => a % b,
+        })
+    }
+}
+
+impl Expr {
+    #[inline]
+    pub fn var(name: &str) -> Expr {
+        Expr::Var(name.to_string())
+    }
+
+    #[inline]
+    pub fn binary(op: BinOp, left: Expr, right: Expr) -> Expr {
+        Expr::Binary(op, Box::new(left), Box::new(right))
+    }
+
+    /// Evaluates the expression, looking up variables with `variable`.
+    pub fn eval<V: Value<Context = ()>>(
+        &self,
+        variable: &impl Fn(&str) -> Option<V>,
+    ) -> Result<V, EvalError> {
+        self.eval_in(&(), variable)
+    }
+
+    /// Evaluates the expression in a value type that needs a context for its constants.
+    pub fn eval_in<V: Value>(
+        &self,
+        context: &V::Context,
+        variable: &impl Fn(&str) -> Option<V>,
+    ) -> Result<V, EvalError> {
+        match self {
+            Expr::Const(c) => V::constant(*c, context),
+            Expr::Var(name) => {
+                variable(name).ok_or_else(|| EvalError::UnknownVariable(name.clone()))
+            }
+            Expr::Neg(e) => e.eval_in(context, variable)?.negate(),
+            Expr::Binary(op, left, right) => V::apply(
+                *op,
+                left.eval_in(context, variable)?,
+                right.eval_in(context, variable)?,
+            ),
+        }
+    }
+
+    /// Evaluates an expression with a single variable `name` set to `value`.
+    pub fn eval_with<V: Value<Context = ()>>(&self, name: &str, value: V) -> Result<V, EvalError> {
+        self.eval(&|n| (n == name).then_some(value))
+    }
+
+    pub fn variables(&self) -> BTreeSet<&str> {
+        let mut variables = BTreeSet::new();
+        self.visit(&mut |e| {
+            if let Expr::Var(name) = e {
+                variables.insert(name.as_str());
+            }
+        });
+        variables
+    }
+
+    fn visit<'a>(&'a self, f: &mut impl FnMut(&'a Expr)) {
+        f(self);
+        match self {
+            Expr::Const(_) | Expr::Var(_) => {}
+            Expr::Neg(e) => e.visit(f),
+            Expr::Binary(_, left, right) => {
+                left.visit(f);
+                right.visit(f);
+            }
+        }
+    }
+
+    /// Replaces every use of the variable `name` with `replacement`, e.g. to compose two
+    /// operations into one.
+    pub fn substitute(&self, name: &str, replacement: &Expr) -> Expr {
+        match self {
+            Expr::Var(n) if n == name => replacement.clone(),
+            Expr::Const(_) | Expr::Var(_) => self.clone(),
+            Expr::Neg(e) => Expr::Neg(Box::new(e.substitute(name, replacement))),
+            Expr::Binary(op, left, right) => Expr::binary(
+                *op,
+                left.substitute(name, replacement),
+                right.substitute(name, replacement),
+            ),
+        }
+    }
+
+    /// Folds constant subexpressions and removes identities like `x * 1` and `x + 0`.
+    /// Subexpressions that fail to evaluate, like a division by zero, are kept as they are.
+    /// Constants are folded in `i64` arithmetic, so the result only means the same for values
+    /// that divide like integers: for a [`ModularInt`](crate::modular::ModularInt), `7 / 2`
+    /// isn't `3`. A variable multiplied by zero is dropped, even if it would be unbound.
+    pub fn simplify(&self) -> Expr {
+        use Expr::{Binary, Const, Neg};
+        match self {
+            Const(_) | Expr::Var(_) => self.clone(),
+            Neg(e) => match e.simplify() {
+                Const(c) if c != i64::MIN => Const(-c),
+                Neg(inner) => *inner,
+                e => Neg(Box::new(e)),
+            },
+            Binary(op, left, right) => match (op, left.simplify(), right.simplify()) {
+                (op, Const(a), Const(b)) => match i64::apply(*op, a, b) {
+                    Ok(c) => Const(c),
+                    Err(_) => Expr::binary(*op, Const(a), Const(b)),
+                },
+                (BinOp::Add, Const(0), e) | (BinOp::Add | BinOp::Sub, e, Const(0)) => e,
+                (BinOp::Mul, Const(1), e) | (BinOp::Mul | BinOp::Div, e, Const(1)) => e,
+                // A variable only fails to evaluate when unbound, anything else might overflow
+                // or divide by zero, so it is kept.
+                (BinOp::Mul, Const(0), Expr::Var(_)) | (BinOp::Mul, Expr::Var(_), Const(0)) => {
+                    Const(0)
+                }
+                (BinOp::Sub, Const(0), e) => Neg(Box::new(e)),
+                (op, left, right) => Expr::binary(*op, left, right),
+            },
+        }
+    }
+
+    /// Writes the expression as `a * name + b`, if it is linear in `name` and has no other
+    /// variables, e.g. to tell whether an operation can be reasoned about modulo something.
+    pub fn linear(&self, name: &str) -> Option<(i64, i64)> {
+        match self {
+            Expr::Const(c) => Some((0, *c)),
+            Expr::Var(n) if n == name => Some((1, 0)),
+            Expr::Var(_) => None,
+            Expr::Neg(e) => {
+                let (a, b) = e.linear(name)?;
+                Some((a.checked_neg()?, b.checked_neg()?))
+            }
+            Expr::Binary(op, left, right) => {
+                let (a1, b1) = left.linear(name)?;
+                let (a2, b2) = right.linear(name)?;
+                match op {
+                    BinOp::Add => Some((a1.checked_add(a2)?, b1.checked_add(b2)?)),
+                    BinOp::Sub => Some((a1.checked_sub(a2)?, b1.checked_sub(b2)?)),
+                    BinOp::Mul if a1 == 0 => Some((b1.checked_mul(a2)?, b1.checked_mul(b2)?)),
+                    BinOp::Mul if a2 == 0 => Some((a1.checked_mul(b2)?, b1.checked_mul(b2)?)),
+                    _ if a1 == 0 && a2 == 0 => Some((0, i64::apply(*op, b1, b2).ok()?)),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut Formatter<'_>, min_precedence: u8) -> std::fmt::Result {
+        match self {
+            Expr::Binary(op, ..) if op.precedence() < min_precedence => write!(f, "({})", self),
+            _ => write!(f, "{}", self),
+        }
+    }
+}
+
+impl Display for Expr {
+    /// Writes the expression with only the parentheses it needs.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Const(c) => write!(f, "{}", c),
+            Expr::Var(name) => write!(f, "{}", name),
+            Expr::Neg(e) => {
+                write!(f, "-")?;
+                match **e {
+                    Expr::Binary(..) | Expr::Neg(_) => write!(f, "({})", e),
+                    _ => write!(f, "{}", e),
+                }
+            }
+            Expr::Binary(op, left, right) => {
+                left.fmt_operand(f, op.precedence())?;
+                write!(f, " {} ", op.symbol())?;
+                // Operators are left associative, so a right operand of the same precedence
+                // needs parentheses: a - (b - c).
+                right.fmt_operand(f, op.precedence() + 1)
+            }
+        }
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            source: s,
+            chars: s.char_indices().peekable(),
+        };
+        let expr = parser.expression(0)?;
+        parser.skip_whitespace();
+        match parser.chars.peek() {
+            None => Ok(expr),
+            Some(_) => Err(parser.error("unexpected character")),
+        }
+    }
+}
+
+/// Precedence climbing over the characters of the source.
+struct Parser<'a> {
+    source: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl Parser<'_> {
+    fn position(&mut self) -> usize {
+        self.chars.peek().map_or(self.source.len(), |(i, _)| *i)
+    }
+
+    fn error(&mut self, message: &str) -> ParseError {
+        ParseError {
+            position: self.position(),
+            message: message.to_string(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &str {
+        let start = self.position();
+        while self.chars.next_if(|(_, c)| predicate(*c)).is_some() {}
+        let end = self.position();
+        &self.source[start..end]
+    }
+
+    fn expression(&mut self, min_precedence: u8) -> Result<Expr, ParseError> {
+        let mut left = self.operand()?;
+        loop {
+            self.skip_whitespace();
+            let op = match self.chars.peek().and_then(|(_, c)| BinOp::from_symbol(*c)) {
+                Some(op) if op.precedence() >= min_precedence => op,
+                _ => return Ok(left),
+            };
+            self.chars.next();
+            let right = self.expression(op.precedence() + 1)?;
+            left = Expr::binary(op, left, right);
+        }
+    }
+
+    fn operand(&mut self) -> Result<Expr, ParseError> {
+        self.skip_whitespace();
+        match self.chars.peek().map(|(_, c)| *c) {
+            Some('(') => {
+                self.chars.next();
+                let expr = self.expression(0)?;
+                self.skip_whitespace();
+                match self.chars.next() {
+                    Some((_, ')')) => Ok(expr),
+                    _ => Err(self.error("expected )")),
+                }
+            }
+            Some('-') => {
+                self.chars.next();
+                Ok(Expr::Neg(Box::new(self.operand()?)))
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let position = self.position();
+                let digits = self.take_while(|c| c.is_ascii_digit());
+                digits.parse().map(Expr::Const).map_err(|_| ParseError {
+                    position,
+                    message: "number too large".to_string(),
+                })
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let name = self.take_while(|c| c.is_alphanumeric() || c == '_');
+                Ok(Expr::var(name))
+            }
+            _ => Err(self.error("expected a number, variable or (")),
+        }
+    }
+}
+
+#[cfg(test)]
+fn parse(s: &str) -> Expr {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_expr_precedence() {
+    assert_eq!(Ok(14), parse("2 + 3 * 4").eval_with("x", 0));
+    assert_eq!(Ok(20), parse("(2 + 3) * 4").eval_with("x", 0));
+    assert_eq!(Ok(3), parse("10 - 4 - 3").eval_with("x", 0));
+    assert_eq!(
+        Ok(2),
+        parse("100 / 10 / 5 % 3 - -(-1) + 1").eval_with("x", 0)
+    );
+    assert_eq!(Ok(79 * 2 + 3), parse("old * 2 + 3").eval_with("old", 79));
+    assert_eq!(Ok(158), parse("old+old").eval_with("old", 79));
+}
+
+#[test]
+fn test_expr_display() {
+    for s in [
+        "a - (b - c)",
+        "(a - b) - c",
+        "a * (b + c) % d",
+        "-(x * 2) + -y",
+        "-5 / x",
+    ] {
+        let expr = parse(s);
+        assert_eq!(expr, parse(&expr.to_string()));
+    }
+    assert_eq!("a - b - c", parse("(a - b) - c").to_string());
+    assert_eq!("a * (b + c) % d", parse("(a * (b + c)) % d").to_string());
+}
+
+#[test]
+fn test_expr_errors() {
+    assert_eq!(
+        Err(EvalError::UnknownVariable("y".to_string())),
+        parse("x + y").eval_with("x", 1)
+    );
+    assert_eq!(
+        Err(EvalError::DivisionByZero),
+        parse("x % 0").eval_with("x", 1)
+    );
+    assert_eq!(
+        Err(EvalError::Overflow),
+        parse("x * x").eval_with("x", i64::MAX)
+    );
+    assert_eq!(
+        Ok(Wrapping(1)),
+        parse("x * x").eval_with("x", Wrapping(i64::MAX))
+    );
+    assert_eq!(
+        Err(EvalError::DivisionByZero),
+        parse("x / 0").eval_with("x", Wrapping(1))
+    );
+
+    let error = "2 * (x + 1".parse::<Expr>().unwrap_err();
+    assert_eq!(10, error.position);
+    assert_eq!(4, "2 + * 3".parse::<Expr>().unwrap_err().position);
+    assert_eq!(6, "x + 1 y".parse::<Expr>().unwrap_err().position);
+}
+
+#[test]
+fn test_expr_symbolic() {
+    assert_eq!(
+        parse("old * 19"),
+        parse("old * (3 + 4 * 4) + 0 * y").simplify()
+    );
+    assert_eq!(parse("x"), parse("--(x * 1 - 0) / 1").simplify());
+    assert_eq!(parse("x / 0"), parse("x / (1 - 1)").simplify());
+    let zeroed = parse("(x / 0) * 0").simplify();
+    assert_eq!(parse("x / 0 * 0"), zeroed);
+    assert_eq!(Err(EvalError::DivisionByZero), zeroed.eval_with("x", 1));
+    assert_eq!(Ok(0), parse("y * 0").simplify().eval_with("x", 1));
+
+    let composed = parse("old * old").substitute("old", &parse("old + 6"));
+    assert_eq!("(old + 6) * (old + 6)", composed.to_string());
+    assert_eq!(None, composed.linear("old"));
+    assert_eq!(Some((6, 18)), parse("(x + 3) * 6").linear("x"));
+    assert_eq!(Some((-1, 10)), parse("10 - x").linear("x"));
+    assert_eq!(None, parse("x * y").linear("x"));
+    assert_eq!(
+        vec!["x", "y"],
+        parse("x * y + x")
+            .variables()
+            .into_iter()
+            .collect::<Vec<_>>()
+    );
+}
@@ -1,12 +1,15 @@
+use crate::gridview::GridView;
 use crate::vec2i::{Bounds, Indexer, Point, Size};
 use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::ops::{Index, IndexMut};
 
+#[derive(Clone)]
 pub struct Grid2d<T> {
     pub bounds: Bounds,
     indexer: Indexer,
-    empty: T,
-    tiles: Vec<T>,
+    pub(crate) empty: T,
+    pub(crate) tiles: Vec<T>,
 }
 
 impl<T> Grid2d<T> {
@@ -16,7 +19,7 @@ impl<T> Grid2d<T> {
     }
 
     #[inline]
-    fn index(&self, p: Point) -> usize {
+    pub(crate) fn index(&self, p: Point) -> usize {
         self.indexer.index(&p)
     }
 
@@ -24,6 +27,82 @@ impl<T> Grid2d<T> {
     pub fn from_index(&self, index: usize) -> Point {
         self.indexer.from_index(index)
     }
+
+    #[inline]
+    pub(crate) fn width(&self) -> usize {
+        self.size().width().max(0) as usize
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (Point, &T)> + ExactSizeIterator + '_ {
+        let indexer = self.indexer;
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(move |(index, tile)| (indexer.from_index(index), tile))
+    }
+
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (Point, &mut T)> + ExactSizeIterator + '_ {
+        let indexer = self.indexer;
+        self.tiles
+            .iter_mut()
+            .enumerate()
+            .map(move |(index, tile)| (indexer.from_index(index), tile))
+    }
+
+    pub fn points_where<'a>(
+        &'a self,
+        predicate: impl Fn(&T) -> bool + 'a,
+    ) -> impl DoubleEndedIterator<Item = Point> + 'a {
+        self.iter()
+            .filter(move |(_, tile)| predicate(tile))
+            .map(|(p, _)| p)
+    }
+
+    pub fn row(&self, y: i32) -> Option<&[T]> {
+        (self.bounds.top()..=self.bounds.bottom())
+            .contains(&y)
+            .then(|| {
+                let start = self.index(Point::new(self.bounds.left(), y));
+                &self.tiles[start..start + self.width()]
+            })
+    }
+
+    pub fn row_mut(&mut self, y: i32) -> Option<&mut [T]> {
+        if (self.bounds.top()..=self.bounds.bottom()).contains(&y) {
+            let start = self.index(Point::new(self.bounds.left(), y));
+            let end = start + self.width();
+            Some(&mut self.tiles[start..end])
+        } else {
+            None
+        }
+    }
+
+    pub fn column(
+        &self,
+        x: i32,
+    ) -> Option<impl DoubleEndedIterator<Item = &T> + ExactSizeIterator + '_> {
+        (self.bounds.left()..=self.bounds.right())
+            .contains(&x)
+            .then(|| {
+                let start = self.index(Point::new(x, self.bounds.top()));
+                self.tiles[start..].iter().step_by(self.width())
+            })
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator + '_ {
+        self.tiles.chunks_exact(self.width().max(1))
+    }
+
+    pub fn columns(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = impl DoubleEndedIterator<Item = &T> + ExactSizeIterator>
+           + ExactSizeIterator
+           + '_ {
+        let width = self.width();
+        (0..width).map(move |column| self.tiles[column..].iter().step_by(width))
+    }
 }
 
 impl<T: Copy> Grid2d<T> {
@@ -173,6 +252,56 @@ impl<T: Copy + Default> Default for Grid2d<T> {
     }
 }
 
+impl<T: PartialEq> Grid2d<T> {
+    /// The smallest bounds containing every non-empty tile.
+    pub fn content_bounds(&self) -> Bounds {
+        self.points_where(|tile| *tile != self.empty)
+            .fold(Bounds::EMPTY, |bounds, p| bounds.extend_to(&p))
+    }
+
+    /// The content bounds with `padding` empty tiles around them, for rendering grids that
+    /// have grown much larger than what's in them.
+    pub fn content_view(&self, padding: i32) -> GridView<'_, T> {
+        self.view(self.content_bounds().expand(padding))
+    }
+}
+
+impl<T: Copy + PartialEq> Grid2d<T> {
+    /// Shrinks the bounds to the content bounds.
+    pub fn trim(&mut self) {
+        let content_bounds = self.content_bounds();
+        if content_bounds != self.bounds {
+            *self = self.view(content_bounds).to_grid();
+        }
+    }
+}
+
+/// Grids are equal when they have the same empty tile and the same tile at every point, so
+/// grids that differ only in how much empty space they cover are equal.
+impl<T: PartialEq> PartialEq for Grid2d<T> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.empty != other.empty {
+            return false;
+        }
+        let content_bounds = self.content_bounds();
+        content_bounds == other.content_bounds()
+            && content_bounds.iter_points().all(|p| self[p] == other[p])
+    }
+}
+
+impl<T: Eq> Eq for Grid2d<T> {}
+
+impl<T: PartialEq + Hash> Hash for Grid2d<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.empty.hash(state);
+        let content_bounds = self.content_bounds();
+        content_bounds.hash(state);
+        content_bounds
+            .iter_points()
+            .for_each(|p| self[p].hash(state));
+    }
+}
+
 impl<T: Display> Debug for Grid2d<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         Display::fmt(self, f)
@@ -232,3 +361,106 @@ fn test_grid2d_resize() {
         grid.to_string()
     );
 }
+
+#[test]
+fn test_grid2d_eq() {
+    use std::collections::hash_map::DefaultHasher;
+    fn hash(grid: &Grid2d<char>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        grid.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let mut small = Grid2d::new('.');
+    small[Point::new(1, 1)] = '#';
+    small[Point::new(2, 1)] = '#';
+
+    let mut large = Grid2d::with_bounds('.', Bounds::new(-3, 3, 3, -3));
+    large[Point::new(2, 1)] = '#';
+    assert_ne!(small, large);
+
+    large[Point::new(1, 1)] = '#';
+    assert_eq!(small, large);
+    assert_eq!(hash(&small), hash(&large));
+
+    large[Point::new(-3, -3)] = 'x';
+    assert_ne!(small, large);
+    assert_ne!(hash(&small), hash(&large));
+
+    assert_eq!(Grid2d::new('.'), Grid2d::with_size('.', [2, 2]));
+    assert_ne!(Grid2d::new('.'), Grid2d::new(' '));
+}
+
+#[test]
+fn test_grid2d_trim() {
+    let mut grid = Grid2d::new('.');
+    grid.line(&Point::new(-2, 0), &Point::new(4, 0), '#');
+    grid[Point::new(1, 3)] = '#';
+    grid.line(&Point::new(-2, 0), &Point::new(4, 0), '.');
+
+    assert_eq!(Bounds::new(0, 4, 3, -2), grid.bounds);
+    assert_eq!(Bounds::point(Point::new(1, 3)), grid.content_bounds());
+    assert_eq!("...\n.#.\n...\n", grid.content_view(1).to_string());
+
+    grid.trim();
+    assert_eq!(Bounds::point(Point::new(1, 3)), grid.bounds);
+    assert_eq!("#\n", grid.to_string());
+
+    grid.clear();
+    grid.trim();
+    assert_eq!(Bounds::EMPTY, grid.bounds);
+    assert_eq!("", grid.to_string());
+    assert_eq!("", grid.content_view(2).to_string());
+}
+
+#[test]
+fn test_grid2d_iter() {
+    let mut grid = Grid2d::with_bounds(0, Bounds::new(-1, 1, 0, -1));
+    grid[Point::new(0, -1)] = 1;
+    grid[Point::new(1, 0)] = 2;
+
+    let tiles = grid.iter().collect::<Vec<_>>();
+    assert_eq!(6, grid.iter().len());
+    assert_eq!((Point::new(-1, -1), &0), tiles[0]);
+    assert_eq!((Point::new(0, -1), &1), tiles[1]);
+    assert_eq!(Some((Point::new(1, 0), &2)), grid.iter().next_back());
+
+    grid.iter_mut().for_each(|(p, tile)| *tile += p.x());
+    assert_eq!(
+        vec![-1, 1, 1, -1, 0, 3],
+        grid.iter().map(|(_, tile)| *tile).collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        vec![Point::new(0, -1), Point::new(1, -1), Point::new(1, 0)],
+        grid.points_where(|tile| *tile > 0).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_grid2d_rows_and_columns() {
+    let grid = Grid2d::from_parts('.', Bounds::new(2, 2, 4, 0), "abcdefghi".chars().collect());
+
+    assert_eq!(Some(&['d', 'e', 'f'][..]), grid.row(3));
+    assert_eq!(None, grid.row(1));
+    assert_eq!(
+        vec!['b', 'e', 'h'],
+        grid.column(1).unwrap().copied().collect::<Vec<_>>()
+    );
+    assert_eq!(Some(&'i'), grid.column(2).unwrap().next_back());
+    assert!(grid.column(3).is_none());
+
+    assert_eq!(3, grid.rows().len());
+    assert_eq!(Some(&['g', 'h', 'i'][..]), grid.rows().next_back());
+    assert_eq!(
+        vec!["adg", "beh", "cfi"],
+        grid.columns()
+            .map(|column| column.collect::<String>())
+            .collect::<Vec<_>>()
+    );
+
+    let empty = Grid2d::new('.');
+    assert_eq!(0, empty.rows().len());
+    assert_eq!(0, empty.columns().len());
+    assert_eq!(0, empty.iter().len());
+}
@@ -0,0 +1,156 @@
+use crate::ansi::{RESET, REVERSE};
+use crate::grid2d::Grid2d;
+use crate::vec2i::{Bounds, Point};
+use std::collections::HashSet;
+use std::fmt::{Debug, Display, Formatter};
+
+/// The points where two grids have different tiles, for showing how they differ, e.g. in test
+/// failure messages.
+pub struct GridDiff<'a, T> {
+    left: &'a Grid2d<T>,
+    right: &'a Grid2d<T>,
+    bounds: Bounds,
+    changed: Vec<Point>,
+}
+
+impl<T: PartialEq> Grid2d<T> {
+    pub fn diff<'a>(&'a self, other: &'a Grid2d<T>) -> GridDiff<'a, T> {
+        let bounds = self.bounds.extend_to_bounds(&other.bounds);
+        // Outside the content of both grids, tiles only differ if the empty tiles do.
+        let compared = if self.empty == other.empty {
+            self.content_bounds()
+                .extend_to_bounds(&other.content_bounds())
+        } else {
+            bounds
+        };
+        let changed = compared
+            .iter_points()
+            .filter(|p| self[*p] != other[*p])
+            .collect();
+        GridDiff {
+            left: self,
+            right: other,
+            bounds,
+            changed,
+        }
+    }
+}
+
+impl<T> GridDiff<'_, T> {
+    #[inline]
+    pub fn changed(&self) -> &[Point] {
+        &self.changed
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty()
+    }
+}
+
+impl<T: Display> GridDiff<'_, T> {
+    /// Renders row `y` of `grid`, with the tiles in `highlight` in reverse video.
+    fn render_row(&self, grid: &Grid2d<T>, y: i32, highlight: &HashSet<Point>) -> String {
+        let mut row = String::new();
+        for x in self.bounds.left()..=self.bounds.right() {
+            let p = Point::new(x, y);
+            if highlight.contains(&p) {
+                row += REVERSE;
+                row += &grid[p].to_string();
+                row += RESET;
+            } else {
+                row += &grid[p].to_string();
+            }
+        }
+        row
+    }
+
+    fn rows(&self) -> impl Iterator<Item = i32> {
+        let is_empty = self.bounds.is_empty();
+        (self.bounds.top()..=self.bounds.bottom()).filter(move |_| !is_empty)
+    }
+
+    /// Both grids next to each other, with a `>` before each row that differs.
+    pub fn side_by_side(&self) -> String {
+        let changed_rows = self.changed.iter().map(|p| p.y()).collect::<HashSet<_>>();
+        let none = HashSet::new();
+        let rows = self
+            .rows()
+            .map(|y| {
+                let changed = changed_rows.contains(&y);
+                let left = self.render_row(self.left, y, &none);
+                let right = self.render_row(self.right, y, &none);
+                (changed, left, right)
+            })
+            .collect::<Vec<_>>();
+        let width = rows
+            .iter()
+            .map(|(_, left, _)| left.chars().count())
+            .max()
+            .unwrap_or(0);
+        rows.into_iter()
+            .map(|(changed, left, right)| {
+                let marker = if changed { '>' } else { ' ' };
+                format!("{} {:width$} | {}\n", marker, left, right, width = width)
+            })
+            .collect()
+    }
+
+    /// The second grid, with the tiles that differ from the first in reverse video.
+    pub fn highlighted(&self) -> String {
+        let changed = self.changed.iter().copied().collect::<HashSet<_>>();
+        self.rows()
+            .map(|y| self.render_row(self.right, y, &changed) + "\n")
+            .collect()
+    }
+}
+
+impl<T: Display> Display for GridDiff<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.side_by_side())
+    }
+}
+
+impl<T: Display> Debug for GridDiff<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[test]
+fn test_grid_diff() {
+    let mut before = Grid2d::with_size('.', [3, 2]);
+    before[Point::new(0, 0)] = '#';
+    let mut after = Grid2d::with_size('.', [3, 2]);
+    after[Point::new(2, 1)] = '#';
+    after[Point::new(3, 1)] = '#';
+
+    let diff = before.diff(&after);
+    assert!(!diff.is_empty());
+    assert_eq!(
+        &[Point::new(0, 0), Point::new(2, 1), Point::new(3, 1)],
+        diff.changed()
+    );
+    assert_eq!("> #... | ....\n> .... | ..##\n", diff.to_string());
+    assert_eq!(
+        "\x1b[7m.\x1b[0m...\n..\x1b[7m#\x1b[0m\x1b[7m#\x1b[0m\n",
+        diff.highlighted()
+    );
+
+    assert!(before.diff(&before).is_empty());
+    assert_eq!(
+        "  #.. | #..\n  ... | ...\n",
+        before.diff(&before).to_string()
+    );
+    assert_eq!("", Grid2d::new('.').diff(&Grid2d::new('.')).to_string());
+
+    let mut far = Grid2d::new('.');
+    far[Point::new(1000, 1000)] = '.';
+    far[Point::new(2, 1)] = '#';
+    far[Point::new(3, 1)] = '#';
+    assert!(after.diff(&far).is_empty());
+    assert_eq!(after, far);
+    let mut dotted = Grid2d::with_size(' ', [4, 2]);
+    dotted[Point::new(0, 0)] = '#';
+    assert_eq!(8, after.diff(&dotted).changed().len());
+}
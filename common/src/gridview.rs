@@ -0,0 +1,271 @@
+#[cfg(test)]
+use crate::charcanvas::CharCanvas;
+use crate::grid2d::Grid2d;
+use crate::vec2i::{Bounds, Point, Size};
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Index, IndexMut};
+
+/// A rectangular window into a [`Grid2d`], indexed with the same points as the grid. Points
+/// in the window but outside the grid read as the grid's empty tile.
+pub struct GridView<'a, T> {
+    grid: &'a Grid2d<T>,
+    bounds: Bounds,
+}
+
+/// A mutable rectangular window into a [`Grid2d`]. Unlike the grid itself it can't grow, so its
+/// bounds must be inside the grid.
+pub struct GridViewMut<'a, T> {
+    grid: &'a mut Grid2d<T>,
+    bounds: Bounds,
+}
+
+impl<T> Grid2d<T> {
+    pub fn view(&self, bounds: Bounds) -> GridView<'_, T> {
+        GridView { grid: self, bounds }
+    }
+
+    pub fn view_mut(&mut self, bounds: Bounds) -> GridViewMut<'_, T> {
+        assert!(
+            self.bounds.contains_bounds(&bounds),
+            "{:?} outside of grid {:?}",
+            bounds,
+            self.bounds
+        );
+        GridViewMut { grid: self, bounds }
+    }
+
+    /// All windows of `size` that fit entirely inside the grid, row by row.
+    pub fn windows(&self, size: impl Into<Size>) -> impl Iterator<Item = GridView<'_, T>> + '_ {
+        let size = size.into();
+        let [grid_width, grid_height]: [i32; 2] = self.size().into();
+        let [width, height]: [i32; 2] = size.into();
+        assert!(width > 0 && height > 0, "windows can't be empty");
+        let top_lefts = if width > grid_width || height > grid_height {
+            Bounds::EMPTY
+        } else {
+            Bounds::with_top_left_and_size(
+                self.bounds.top_left(),
+                [grid_width - width + 1, grid_height - height + 1],
+            )
+        };
+        top_lefts
+            .iter_points()
+            .map(move |top_left| self.view(Bounds::with_top_left_and_size(top_left, size)))
+    }
+}
+
+impl<'a, T> GridView<'a, T> {
+    #[inline]
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &'a T)> + '_ {
+        let grid = self.grid;
+        self.bounds.iter_points().map(move |p| (p, &grid[p]))
+    }
+
+    /// The tiles in row order, for comparing windows at different positions.
+    pub fn tiles(&self) -> impl Iterator<Item = &'a T> + '_ {
+        self.iter().map(|(_, tile)| tile)
+    }
+}
+
+impl<T: Copy> GridView<'_, T> {
+    pub fn to_grid(&self) -> Grid2d<T> {
+        Grid2d::from_parts(
+            self.grid.empty,
+            self.bounds,
+            self.tiles().copied().collect(),
+        )
+    }
+}
+
+impl<T> Index<Point> for GridView<'_, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, p: Point) -> &Self::Output {
+        if self.bounds.contains(&p) {
+            &self.grid[p]
+        } else {
+            &self.grid.empty
+        }
+    }
+}
+
+impl<T> GridViewMut<'_, T> {
+    #[inline]
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    pub fn as_view(&self) -> GridView<'_, T> {
+        GridView {
+            grid: self.grid,
+            bounds: self.bounds,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Point, &mut T)> + '_ {
+        let bounds = self.bounds;
+        let grid_bounds = self.grid.bounds;
+        let width = self.grid.width().max(1);
+        let skip_rows = (bounds.top() - grid_bounds.top()) as usize;
+        let skip_columns = (bounds.left() - grid_bounds.left()) as usize;
+        let columns = bounds.size().width().max(0) as usize;
+        let rows = bounds.size().height().max(0) as usize;
+        self.grid
+            .tiles
+            .chunks_exact_mut(width)
+            .skip(skip_rows)
+            .take(rows)
+            .zip(bounds.top()..)
+            .flat_map(move |(row, y)| {
+                row[skip_columns..skip_columns + columns]
+                    .iter_mut()
+                    .zip(bounds.left()..)
+                    .map(move |(tile, x)| (Point::new(x, y), tile))
+            })
+    }
+}
+
+impl<T: Copy> GridViewMut<'_, T> {
+    pub fn fill(&mut self, tile: T) {
+        self.iter_mut().for_each(|(_, t)| *t = tile);
+    }
+}
+
+impl<T> Index<Point> for GridViewMut<'_, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, p: Point) -> &Self::Output {
+        if self.bounds.contains(&p) {
+            &self.grid[p]
+        } else {
+            &self.grid.empty
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for GridViewMut<'_, T> {
+    #[inline]
+    fn index_mut(&mut self, p: Point) -> &mut Self::Output {
+        assert!(
+            self.bounds.contains(&p),
+            "{:?} outside of view {:?}",
+            p,
+            self.bounds
+        );
+        let index = self.grid.index(p);
+        &mut self.grid.tiles[index]
+    }
+}
+
+fn fmt_view<T: Display>(
+    view: &impl Index<Point, Output = T>,
+    bounds: Bounds,
+    f: &mut Formatter<'_>,
+) -> std::fmt::Result {
+    if bounds.is_empty() {
+        return Ok(());
+    }
+    for y in bounds.top()..=bounds.bottom() {
+        for x in bounds.left()..=bounds.right() {
+            f.pad(&view[Point::new(x, y)].to_string())?;
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+impl<T: Display> Display for GridView<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fmt_view(self, self.bounds, f)
+    }
+}
+
+impl<T: Display> Debug for GridView<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl<T: Display> Display for GridViewMut<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fmt_view(self, self.bounds, f)
+    }
+}
+
+impl<T: Display> Debug for GridViewMut<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[test]
+fn test_grid_view() {
+    let grid = CharCanvas::from_text('.', [-1, -1], "abcd\nefgh\nijkl");
+    let view = grid.view(Bounds::new(0, 2, 2, 1));
+    assert_eq!('g', view[Point::new(1, 0)]);
+    assert_eq!('.', view[Point::new(1, 2)]);
+    assert_eq!('.', view[Point::new(0, 0)]);
+    assert_eq!("gh\nkl\n..\n", view.to_string());
+    assert_eq!("ghkl..", view.tiles().collect::<String>());
+    assert_eq!(Some((Point::new(2, 1), &'l')), view.iter().nth(3));
+
+    let copy = grid.view(Bounds::new(-1, 0, 0, -1)).to_grid();
+    assert_eq!("ab\nef\n", copy.to_string());
+    assert_eq!(Point::new(-1, -1), copy.bounds.top_left());
+}
+
+#[test]
+fn test_grid_view_mut() {
+    let mut grid = CharCanvas::from_text('.', [-1, -1], "abcd\nefgh\nijkl");
+    let mut view = grid.view_mut(Bounds::new(0, 1, 1, 0));
+    view[Point::new(0, 0)] = 'X';
+    assert_eq!('X', view.as_view()[Point::new(0, 0)]);
+    view.iter_mut()
+        .filter(|(p, _)| p.y() == 1)
+        .for_each(|(_, tile)| *tile = 'Y');
+    assert_eq!("Xg\nYY\n", view.to_string());
+    assert_eq!("abcd\neXgh\niYYl\n", grid.to_string());
+
+    grid.view_mut(Bounds::new(-1, 2, -1, 1)).fill('#');
+    assert_eq!("ab##\neXgh\niYYl\n", grid.to_string());
+}
+
+#[test]
+#[should_panic]
+fn test_grid_view_mut_outside_grid() {
+    let mut grid = CharCanvas::from_text('.', [-1, -1], "abcd\nefgh\nijkl");
+    grid.view_mut(Bounds::new(0, 3, 1, 0));
+}
+
+#[test]
+fn test_grid_windows() {
+    let grid = CharCanvas::from_text('.', [-1, -1], "abcd\nefgh\nijkl");
+    let windows = grid.windows([3, 3]).collect::<Vec<_>>();
+    assert_eq!(2, windows.len());
+    assert_eq!("abc\nefg\nijk\n", windows[0].to_string());
+    assert_eq!("bcd\nfgh\njkl\n", windows[1].to_string());
+
+    assert_eq!(6, grid.windows([2, 2]).count());
+    assert_eq!(12, grid.windows([1, 1]).count());
+    assert_eq!(0, grid.windows([5, 1]).count());
+    assert_eq!(
+        1,
+        grid.windows([2, 2])
+            .filter(|w| w.tiles().eq(['f', 'g', 'j', 'k'].iter()))
+            .count()
+    );
+}
+
+#[test]
+#[should_panic(expected = "windows can't be empty")]
+fn test_grid_windows_empty() {
+    CharCanvas::from_text('.', [0, 0], "ab\ncd")
+        .windows([0, 1])
+        .count();
+}
@@ -0,0 +1,278 @@
+use crate::charcanvas::CharCanvas;
+use crate::vec2i::Point;
+use std::fmt::{Debug, Formatter};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::simd::{Simd, SimdInt};
+
+/// A hex in axial coordinates `(q, r)`, with the implicit third cube coordinate `s = -q - r`.
+/// Directions and rendering assume pointy-top hexes, with `r` growing downwards.
+#[derive(Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Hex(Simd<i32, 2>);
+
+pub const EAST: Hex = Hex::new(1, 0);
+pub const NORTH_EAST: Hex = Hex::new(1, -1);
+pub const NORTH_WEST: Hex = Hex::new(0, -1);
+pub const WEST: Hex = Hex::new(-1, 0);
+pub const SOUTH_WEST: Hex = Hex::new(-1, 1);
+pub const SOUTH_EAST: Hex = Hex::new(0, 1);
+
+pub const DIRECTIONS: [Hex; 6] = [EAST, NORTH_EAST, NORTH_WEST, WEST, SOUTH_WEST, SOUTH_EAST];
+
+/// How hexes are laid out in rows and columns of an offset grid: pointy-top hexes in rows where
+/// the odd or even rows are shoved right, or flat-top hexes in columns where the odd or even
+/// columns are shoved down.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OffsetLayout {
+    OddR,
+    EvenR,
+    OddQ,
+    EvenQ,
+}
+
+impl Hex {
+    const Q: usize = 0;
+    const R: usize = 1;
+
+    #[inline]
+    pub const fn new(q: i32, r: i32) -> Self {
+        Self(Simd::from_array([q, r]))
+    }
+
+    #[inline]
+    pub const fn from_cube([q, r, s]: [i32; 3]) -> Self {
+        debug_assert!(q + r + s == 0);
+        Self::new(q, r)
+    }
+
+    #[inline]
+    pub const fn q(&self) -> i32 {
+        self.0.as_array()[Self::Q]
+    }
+
+    #[inline]
+    pub const fn r(&self) -> i32 {
+        self.0.as_array()[Self::R]
+    }
+
+    #[inline]
+    pub const fn s(&self) -> i32 {
+        -self.q() - self.r()
+    }
+
+    #[inline]
+    pub const fn cube(&self) -> [i32; 3] {
+        [self.q(), self.r(), self.s()]
+    }
+
+    #[inline]
+    pub fn distance(&self, other: &Hex) -> i32 {
+        let difference = *self - *other;
+        let qr = difference.0.abs().reduce_max();
+        qr.max(difference.s().abs())
+    }
+
+    pub fn neighbors(&self) -> impl Iterator<Item = Hex> {
+        let center = *self;
+        DIRECTIONS
+            .into_iter()
+            .map(move |direction| center + direction)
+    }
+
+    /// The hexes at exactly `radius` steps away, starting south west and going around
+    /// counterclockwise.
+    pub fn ring(&self, radius: i32) -> impl Iterator<Item = Hex> {
+        assert!(radius >= 0, "a ring can't have a negative radius");
+        let center = *self;
+        let start = center + SOUTH_WEST * radius;
+        let sides = if radius == 0 { 1 } else { 6 };
+        let side_length = radius.max(1);
+        (0..sides).flat_map(move |side| {
+            let corner = DIRECTIONS[..side]
+                .iter()
+                .fold(start, |corner, direction| corner + *direction * radius);
+            (0..side_length).map(move |i| corner + DIRECTIONS[side] * i)
+        })
+    }
+
+    /// All hexes at most `radius` steps away, ring by ring from the center outwards.
+    pub fn spiral(&self, radius: i32) -> impl Iterator<Item = Hex> {
+        let center = *self;
+        (0..=radius).flat_map(move |ring| center.ring(ring))
+    }
+
+    pub const fn to_offset(&self, layout: OffsetLayout) -> Point {
+        let (q, r) = (self.q(), self.r());
+        match layout {
+            OffsetLayout::OddR => Point::new(q + (r - (r & 1)) / 2, r),
+            OffsetLayout::EvenR => Point::new(q + (r + (r & 1)) / 2, r),
+            OffsetLayout::OddQ => Point::new(q, r + (q - (q & 1)) / 2),
+            OffsetLayout::EvenQ => Point::new(q, r + (q + (q & 1)) / 2),
+        }
+    }
+
+    pub const fn from_offset(p: &Point, layout: OffsetLayout) -> Self {
+        let (column, row) = (p.x(), p.y());
+        match layout {
+            OffsetLayout::OddR => Self::new(column - (row - (row & 1)) / 2, row),
+            OffsetLayout::EvenR => Self::new(column - (row + (row & 1)) / 2, row),
+            OffsetLayout::OddQ => Self::new(column, row - (column - (column & 1)) / 2),
+            OffsetLayout::EvenQ => Self::new(column, row - (column + (column & 1)) / 2),
+        }
+    }
+
+    /// Position on a character canvas, where each hex is two characters wide and every row is
+    /// shifted half a hex to the right of the one above.
+    #[inline]
+    pub const fn canvas_point(&self) -> Point {
+        Point::new(2 * self.q() + self.r(), self.r())
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    #[inline]
+    fn add(self, rhs: Hex) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Hex {
+    #[inline]
+    fn add_assign(&mut self, rhs: Hex) {
+        self.0 += rhs.0
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    #[inline]
+    fn sub(self, rhs: Hex) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for Hex {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Hex) {
+        self.0 -= rhs.0
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    #[inline]
+    fn mul(self, rhs: i32) -> Self::Output {
+        Self(self.0 * Simd::splat(rhs))
+    }
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+impl Debug for Hex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Hex")
+            .field("q", &self.q())
+            .field("r", &self.r())
+            .field("s", &self.s())
+            .finish()
+    }
+}
+
+pub fn render(hexes: impl IntoIterator<Item = (Hex, char)>) -> CharCanvas {
+    let mut canvas = CharCanvas::new(' ');
+    for (hex, c) in hexes {
+        canvas[hex.canvas_point()] = c;
+    }
+    canvas
+}
+
+#[test]
+fn test_hex_distance() {
+    let origin = Hex::default();
+    assert_eq!(0, origin.distance(&origin));
+    assert!(origin.neighbors().all(|n| origin.distance(&n) == 1));
+    assert_eq!(3, Hex::new(1, -3).distance(&Hex::new(0, 0)));
+    assert_eq!(5, Hex::new(-2, 3).distance(&Hex::new(3, -1)));
+    assert_eq!([1, -3, 2], Hex::new(1, -3).cube());
+    assert_eq!(Hex::new(1, -3), Hex::from_cube([1, -3, 2]));
+}
+
+#[test]
+fn test_hex_ring() {
+    let center = Hex::new(2, -1);
+    assert_eq!(vec![center], center.ring(0).collect::<Vec<_>>());
+    for radius in 1..5 {
+        let ring = center.ring(radius).collect::<Vec<_>>();
+        assert_eq!(6 * radius as usize, ring.len());
+        assert!(ring.iter().all(|h| center.distance(h) == radius));
+        assert!(ring.windows(2).all(|w| w[0].distance(&w[1]) == 1));
+        assert_eq!(1, ring[0].distance(ring.last().unwrap()));
+    }
+}
+
+#[test]
+#[should_panic(expected = "a ring can't have a negative radius")]
+fn test_hex_ring_negative() {
+    Hex::default().ring(-1).count();
+}
+
+#[test]
+fn test_hex_spiral() {
+    let spiral = Hex::default()
+        .spiral(3)
+        .collect::<std::collections::HashSet<_>>();
+    assert_eq!(1 + 3 * 3 * 4, spiral.len());
+}
+
+#[test]
+fn test_hex_offset() {
+    let layouts = [
+        OffsetLayout::OddR,
+        OffsetLayout::EvenR,
+        OffsetLayout::OddQ,
+        OffsetLayout::EvenQ,
+    ];
+    for hex in Hex::new(-1, 1).spiral(4) {
+        for layout in layouts {
+            assert_eq!(hex, Hex::from_offset(&hex.to_offset(layout), layout));
+        }
+    }
+    assert_eq!(
+        Point::new(1, 3),
+        Hex::new(0, 3).to_offset(OffsetLayout::OddR)
+    );
+    assert_eq!(
+        Point::new(2, 3),
+        Hex::new(0, 3).to_offset(OffsetLayout::EvenR)
+    );
+    assert_eq!(
+        Point::new(3, 1),
+        Hex::new(3, 0).to_offset(OffsetLayout::OddQ)
+    );
+    assert_eq!(
+        Point::new(3, 2),
+        Hex::new(3, 0).to_offset(OffsetLayout::EvenQ)
+    );
+}
+
+#[test]
+fn test_hex_render() {
+    let center = Hex::default();
+    let canvas = render(
+        center
+            .ring(1)
+            .map(|h| (h, 'x'))
+            .chain(std::iter::once((center, 'o'))),
+    );
+    assert_eq!(" x x \nx o x\n x x \n", canvas.to_string());
+}
@@ -0,0 +1,287 @@
+use crate::grid2d::Grid2d;
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    /// Draws every tile of `grid` as a `scale` by `scale` square of the color `color` picks.
+    pub fn from_grid<T>(grid: &Grid2d<T>, scale: usize, color: impl Fn(&T) -> Rgb) -> Self {
+        let [width, height]: [i32; 2] = grid.size().into();
+        let mut image = Self::new(
+            width.max(0) as usize * scale,
+            height.max(0) as usize * scale,
+            BLACK,
+        );
+        let top_left = grid.bounds.top_left();
+        for (p, tile) in grid.iter() {
+            let offset = p.vector(&top_left);
+            let x = offset.x() as usize * scale;
+            let y = offset.y() as usize * scale;
+            image.fill_rect(x, y, scale, scale, color(tile));
+        }
+        image
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn pixels(&self) -> &[Rgb] {
+        &self.pixels
+    }
+
+    #[inline]
+    pub fn pixel(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    #[inline]
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: Rgb) {
+        self.pixels[y * self.width + x] = color;
+    }
+
+    /// Fills a rectangle, clipped to the image.
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Rgb) {
+        let right = (x + width).min(self.width);
+        let bottom = (y + height).min(self.height);
+        for row in y.min(bottom)..bottom {
+            let start = row * self.width;
+            self.pixels[start + x.min(right)..start + right].fill(color);
+        }
+    }
+
+    pub fn write_ppm(&self, w: &mut impl Write) -> std::io::Result<()> {
+        write!(w, "P6\n{} {}\n255\n", self.width, self.height)?;
+        w.write_all(&self.pixels.concat())
+    }
+
+    pub fn write_png(&self, w: &mut impl Write) -> std::io::Result<()> {
+        w.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // 8 bits per channel, RGB, default compression, filtering and no interlacing
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+        write_png_chunk(w, b"IHDR", &header)?;
+
+        let mut scanlines = Vec::with_capacity(self.height * (1 + self.width * 3));
+        for row in self.pixels.chunks_exact(self.width.max(1)) {
+            scanlines.push(0);
+            scanlines.extend(row.iter().flatten());
+        }
+        write_png_chunk(w, b"IDAT", &zlib_stored(&scanlines))?;
+        write_png_chunk(w, b"IEND", &[])
+    }
+
+    /// Writes a PNG or, if the path ends with `.ppm`, a PPM.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let mut file = BufWriter::new(File::create(path)?);
+        if path.extension() == Some(OsStr::new("ppm")) {
+            self.write_ppm(&mut file)?;
+        } else {
+            self.write_png(&mut file)?;
+        }
+        file.flush()
+    }
+}
+
+fn write_png_chunk(w: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> std::io::Result<()> {
+    w.write_all(&(data.len() as u32).to_be_bytes())?;
+    w.write_all(kind)?;
+    w.write_all(data)?;
+    let crc = crc32(&[&kind[..], data].concat());
+    w.write_all(&crc.to_be_bytes())
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, byte| {
+        (0..8).fold(crc ^ *byte as u32, |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (a, b) = data.iter().fold((1u32, 0u32), |(a, b), byte| {
+        let a = (a + *byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    (b << 16) | a
+}
+
+/// A zlib stream of uncompressed deflate blocks, which is all a PNG decoder needs.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = 0xffff;
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(last as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+/// Color gradients for numeric grids.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ColorMap {
+    Grayscale,
+    /// Black through red and yellow to white.
+    Heat,
+    /// Deep blue through green and brown to white, for elevations.
+    Terrain,
+}
+
+impl ColorMap {
+    const HEAT: [Rgb; 4] = [BLACK, [255, 0, 0], [255, 255, 0], WHITE];
+    const TERRAIN: [Rgb; 5] = [
+        [0, 0, 128],
+        [0, 160, 64],
+        [224, 224, 96],
+        [128, 80, 32],
+        WHITE,
+    ];
+
+    /// The color at `t`, where 0 is the low end of the gradient and 1 the high end.
+    pub fn color(&self, t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            ColorMap::Grayscale => [(t * 255.0).round() as u8; 3],
+            ColorMap::Heat => interpolate(&Self::HEAT, t),
+            ColorMap::Terrain => interpolate(&Self::TERRAIN, t),
+        }
+    }
+
+    /// A tile to color function spreading the gradient over `min..=max`.
+    pub fn for_range<T: Copy + Into<f64>>(self, min: T, max: T) -> impl Fn(&T) -> Rgb {
+        let min = min.into();
+        let range = max.into() - min;
+        move |v| {
+            let t = if range == 0.0 {
+                0.0
+            } else {
+                ((*v).into() - min) / range
+            };
+            self.color(t)
+        }
+    }
+}
+
+fn interpolate(stops: &[Rgb], t: f64) -> Rgb {
+    let position = t * (stops.len() - 1) as f64;
+    let index = (position.floor() as usize).min(stops.len() - 2);
+    let fraction = position - index as f64;
+    let [from, to] = [stops[index], stops[index + 1]];
+    [0, 1, 2].map(|c| {
+        let from = from[c] as f64;
+        let to = to[c] as f64;
+        (from + (to - from) * fraction).round() as u8
+    })
+}
+
+#[test]
+fn test_checksums() {
+    assert_eq!(0xcbf43926, crc32(b"123456789"));
+    assert_eq!(0xae426082, crc32(b"IEND"));
+    assert_eq!(0x11e60398, adler32(b"Wikipedia"));
+}
+
+#[test]
+fn test_zlib_stored() {
+    let data = (0..70_000).map(|i| i as u8).collect::<Vec<_>>();
+    let stream = zlib_stored(&data);
+    assert_eq!(2 + 5 + 0xffff + 5 + (70_000 - 0xffff) + 4, stream.len());
+    assert_eq!(0, stream[2]);
+    assert_eq!(1, stream[2 + 5 + 0xffff]);
+    assert_eq!(
+        &[0x78, 0x01, 1, 0, 0, 0xff, 0xff, 0, 0, 0, 1],
+        &zlib_stored(&[])[..]
+    );
+}
+
+#[test]
+fn test_image_from_grid() {
+    use crate::vec2i::{Bounds, Point};
+
+    let mut grid = Grid2d::with_bounds(0i8, Bounds::new(-1, 1, 0, -1));
+    grid[Point::new(1, 0)] = 25;
+    let image = Image::from_grid(&grid, 2, ColorMap::Grayscale.for_range(0, 25));
+    assert_eq!((6, 4), (image.width(), image.height()));
+    assert_eq!(BLACK, image.pixel(0, 0));
+    assert_eq!(BLACK, image.pixel(3, 3));
+    assert_eq!(WHITE, image.pixel(4, 2));
+    assert_eq!(WHITE, image.pixel(5, 3));
+
+    let mut ppm = vec![];
+    image.write_ppm(&mut ppm).unwrap();
+    assert!(ppm.starts_with(b"P6\n6 4\n255\n"));
+    assert_eq!(11 + 6 * 4 * 3, ppm.len());
+}
+
+#[test]
+fn test_image_png() {
+    let mut image = Image::new(3, 2, WHITE);
+    image.set_pixel(1, 1, [255, 0, 0]);
+    let mut png = vec![];
+    image.write_png(&mut png).unwrap();
+
+    assert_eq!(b"\x89PNG\r\n\x1a\n", &png[..8]);
+    assert_eq!(b"IHDR", &png[12..16]);
+    assert_eq!(&[0, 0, 0, 3, 0, 0, 0, 2, 8, 2, 0, 0, 0], &png[16..29]);
+    let idat_length = u32::from_be_bytes(png[33..37].try_into().unwrap()) as usize;
+    assert_eq!(b"IDAT", &png[37..41]);
+    assert_eq!(2 + 5 + 2 * (1 + 3 * 3) + 4, idat_length);
+    assert_eq!(b"IEND\xae\x42\x60\x82", &png[png.len() - 8..]);
+}
+
+#[test]
+fn test_color_maps() {
+    assert_eq!(BLACK, ColorMap::Grayscale.color(0.0));
+    assert_eq!([128; 3], ColorMap::Grayscale.color(0.5));
+    assert_eq!(WHITE, ColorMap::Heat.color(1.0));
+    assert_eq!([255, 0, 0], ColorMap::Heat.color(1.0 / 3.0));
+    assert_eq!([0, 0, 128], ColorMap::Terrain.color(-1.0));
+    assert_eq!(WHITE, ColorMap::Terrain.color(2.0));
+    assert_eq!(BLACK, ColorMap::Heat.for_range(5, 5)(&5));
+}
@@ -0,0 +1,287 @@
+use std::cmp::{max, min};
+use std::fmt::Debug;
+use std::iter::once;
+use std::ops::RangeInclusive;
+
+/// The integer types an [`IntervalSet`] can hold.
+pub trait Integer: Copy + Ord + Debug {
+    fn checked_next(self) -> Option<Self>;
+    fn checked_prev(self) -> Option<Self>;
+
+    /// The number of integers in `start..=end`, saturating at `u64::MAX`.
+    fn span(start: Self, end: Self) -> u64;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                #[inline]
+                fn checked_next(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                #[inline]
+                fn checked_prev(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                #[inline]
+                fn span(start: Self, end: Self) -> u64 {
+                    (end as i128 - start as i128 + 1)
+                        .try_into()
+                        .unwrap_or(u64::MAX)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// A set of integers, stored as sorted, disjoint and non-adjacent inclusive ranges.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: vec![] }
+    }
+}
+
+impl<T: Integer> IntervalSet<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The number of integers in the set, saturating at `u64::MAX`.
+    pub fn len(&self) -> u64 {
+        self.ranges.iter().fold(0u64, |len, &(start, end)| {
+            len.saturating_add(T::span(start, end))
+        })
+    }
+
+    /// The number of disjoint ranges the set consists of.
+    #[inline]
+    pub fn range_count(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// The disjoint ranges of the set, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(start, end)| start..=end)
+    }
+
+    /// The ranges missing between the first and the last range of the set.
+    pub fn gaps(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.windows(2).map(|pair| {
+            let (_, end) = pair[0];
+            let (start, _) = pair[1];
+            end.checked_next().unwrap()..=start.checked_prev().unwrap()
+        })
+    }
+
+    #[inline]
+    pub fn first(&self) -> Option<T> {
+        self.ranges.first().map(|&(start, _)| start)
+    }
+
+    #[inline]
+    pub fn last(&self) -> Option<T> {
+        self.ranges.last().map(|&(_, end)| end)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|&(_, end)| end < value);
+        matches!(self.ranges.get(i), Some(&(start, _)) if start <= value)
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        // Ranges that overlap or touch the new one are merged into it.
+        let i = self
+            .ranges
+            .partition_point(|&(_, e)| matches!(e.checked_next(), Some(next) if next < start));
+        let j = self
+            .ranges
+            .partition_point(|&(s, _)| s <= end || end.checked_next() == Some(s));
+        let merged = if i < j {
+            (min(start, self.ranges[i].0), max(end, self.ranges[j - 1].1))
+        } else {
+            (start, end)
+        };
+        self.ranges.splice(i..j, once(merged));
+    }
+
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        let i = self.ranges.partition_point(|&(_, e)| e < start);
+        let j = self.ranges.partition_point(|&(s, _)| s <= end);
+        if i == j {
+            return;
+        }
+        let (first, _) = self.ranges[i];
+        let (_, last) = self.ranges[j - 1];
+        let before = (first < start).then(|| (first, start.checked_prev().unwrap()));
+        let after = (last > end).then(|| (end.checked_next().unwrap(), last));
+        self.ranges.splice(i..j, before.into_iter().chain(after));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for range in other.iter() {
+            union.insert(range);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a_start, a_end) = self.ranges[i];
+            let (b_start, b_end) = other.ranges[j];
+            let start = max(a_start, b_start);
+            let end = min(a_end, b_end);
+            if start <= end {
+                ranges.push((start, end));
+            }
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for range in other.iter() {
+            difference.remove(range);
+        }
+        difference
+    }
+
+    /// The integers within `bounds` that are not in the set.
+    pub fn complement(&self, bounds: RangeInclusive<T>) -> Self {
+        Self::from(bounds).difference(self)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.intersection(other) == *self
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+}
+
+impl<T: Integer> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: Integer> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+impl<T: Integer> Extend<RangeInclusive<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = RangeInclusive<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+#[cfg(test)]
+fn ranges<T: Integer>(set: &IntervalSet<T>) -> Vec<RangeInclusive<T>> {
+    set.iter().collect()
+}
+
+#[test]
+fn test_intervals_insert() {
+    let mut set = IntervalSet::new();
+    set.insert(10..=12);
+    set.insert(1..=3);
+    set.insert(RangeInclusive::new(6, 5));
+    assert_eq!(vec![1..=3, 10..=12], ranges(&set));
+    set.insert(4..=4);
+    assert_eq!(vec![1..=4, 10..=12], ranges(&set));
+    set.insert(8..=9);
+    assert_eq!(vec![1..=4, 8..=12], ranges(&set));
+    set.insert(0..=20);
+    assert_eq!(vec![0..=20], ranges(&set));
+    assert_eq!(21, set.len());
+    assert_eq!(1, set.range_count());
+    assert!(set.contains(0) && set.contains(20));
+    assert!(!set.contains(-1) && !set.contains(21));
+}
+
+#[test]
+fn test_intervals_remove() {
+    let mut set = IntervalSet::from(0..=20);
+    set.remove(5..=7);
+    assert_eq!(vec![0..=4, 8..=20], ranges(&set));
+    set.remove(-5..=0);
+    set.remove(20..=30);
+    assert_eq!(vec![1..=4, 8..=19], ranges(&set));
+    set.remove(4..=8);
+    assert_eq!(vec![1..=3, 9..=19], ranges(&set));
+    set.remove(0..=100);
+    assert!(set.is_empty());
+}
+
+#[test]
+fn test_intervals_set_operations() {
+    let a = [0..=5, 10..=15].into_iter().collect::<IntervalSet<i32>>();
+    let b = [3..=11, 20..=20].into_iter().collect::<IntervalSet<i32>>();
+    assert_eq!(vec![0..=15, 20..=20], ranges(&a.union(&b)));
+    assert_eq!(vec![3..=5, 10..=11], ranges(&a.intersection(&b)));
+    assert_eq!(vec![0..=2, 12..=15], ranges(&a.difference(&b)));
+    assert_eq!(
+        vec![-2..=-1, 6..=9, 16..=17],
+        ranges(&a.complement(-2..=17))
+    );
+    assert_eq!(vec![6..=9], a.gaps().collect::<Vec<_>>());
+    assert!(a.overlaps(&b));
+    assert!(!a.is_subset(&b));
+    assert!(IntervalSet::from(11..=12).is_subset(&a));
+    assert_eq!((Some(0), Some(15)), (a.first(), a.last()));
+}
+
+#[test]
+fn test_intervals_extremes() {
+    let mut set = IntervalSet::from(u8::MIN..=u8::MAX);
+    assert_eq!(256, set.len());
+    set.remove(0..=0);
+    set.remove(255..=255);
+    assert_eq!(vec![1..=254], ranges(&set));
+    set.insert(255..=255);
+    set.insert(0..=0);
+    assert_eq!(vec![0..=255], ranges(&set));
+    assert_eq!(u64::MAX, IntervalSet::from(i64::MIN..=i64::MAX).len());
+}
@@ -1,7 +1,24 @@
 #![feature(const_trait_impl)]
 #![feature(portable_simd)]
 
+pub mod ansi;
+pub mod automaton;
 pub mod charcanvas;
+pub mod cycle;
+pub mod diamond;
+pub mod expr;
 pub mod grid2d;
+pub mod griddiff;
+pub mod gridview;
+pub mod hex;
+pub mod image;
+pub mod intervals;
 pub mod iter;
+pub mod modular;
+pub mod ocr;
+pub mod parallel;
+pub mod recorder;
+pub mod render;
+pub mod rotation;
 pub mod vec2i;
+pub mod vm;
@@ -0,0 +1,222 @@
+use crate::expr::{BinOp, EvalError, Value};
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// An integer modulo a modulus chosen at runtime. Products are computed in 128 bits, so any
+/// modulus up to `u64::MAX` works without overflowing. Mixing values with different moduli
+/// panics.
+#[derive(Copy, Clone, Eq, Hash, PartialEq)]
+pub struct ModularInt {
+    value: u64,
+    modulus: u64,
+}
+
+impl ModularInt {
+    pub fn new(value: i64, modulus: u64) -> Self {
+        assert!(modulus > 0, "modulus must be positive");
+        Self {
+            value: (value as i128).rem_euclid(modulus as i128) as u64,
+            modulus,
+        }
+    }
+
+    #[inline]
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    #[inline]
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    #[inline]
+    fn with_value(&self, value: u128) -> Self {
+        Self {
+            value: (value % self.modulus as u128) as u64,
+            modulus: self.modulus,
+        }
+    }
+
+    #[inline]
+    fn check_modulus(&self, other: &Self) {
+        assert_eq!(
+            self.modulus, other.modulus,
+            "values with different moduli can't be combined"
+        );
+    }
+
+    pub fn pow(&self, mut exponent: u64) -> Self {
+        let mut base = *self;
+        let mut result = self.with_value(1);
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            exponent >>= 1;
+        }
+        result
+    }
+
+    /// The value that multiplied with this one gives 1, if there is one.
+    pub fn inverse(&self) -> Option<Self> {
+        let (mut r0, mut r1) = (self.modulus as i128, self.value as i128);
+        let (mut t0, mut t1) = (0i128, 1i128);
+        while r1 != 0 {
+            let q = r0 / r1;
+            (r0, r1) = (r1, r0 - q * r1);
+            (t0, t1) = (t1, t0 - q * t1);
+        }
+        (r0 == 1).then(|| self.with_value(t0.rem_euclid(self.modulus as i128) as u128))
+    }
+
+    /// Whether the integer this stands for is divisible by `divisor`. That can only be told
+    /// when `divisor` divides the modulus, so it panics otherwise.
+    pub fn is_divisible_by(&self, divisor: u64) -> bool {
+        assert!(
+            self.modulus.checked_rem(divisor) == Some(0),
+            "{} does not divide the modulus {}",
+            divisor,
+            self.modulus
+        );
+        self.value.checked_rem(divisor) == Some(0)
+    }
+}
+
+impl Add for ModularInt {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        self.check_modulus(&rhs);
+        self.with_value(self.value as u128 + rhs.value as u128)
+    }
+}
+
+impl Sub for ModularInt {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.check_modulus(&rhs);
+        self.with_value(self.value as u128 + self.modulus as u128 - rhs.value as u128)
+    }
+}
+
+impl Mul for ModularInt {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.check_modulus(&rhs);
+        self.with_value(self.value as u128 * rhs.value as u128)
+    }
+}
+
+impl Neg for ModularInt {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        self.with_value(self.modulus as u128 - self.value as u128)
+    }
+}
+
+impl Display for ModularInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl Debug for ModularInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (mod {})", self.value, self.modulus)
+    }
+}
+
+/// Evaluates expressions modulo the context's modulus. Division multiplies by the inverse, and
+/// `%` only works for divisors of the modulus.
+impl Value for ModularInt {
+    type Context = u64;
+
+    #[inline]
+    fn constant(c: i64, modulus: &u64) -> Result<Self, EvalError> {
+        Ok(ModularInt::new(c, *modulus))
+    }
+
+    #[inline]
+    fn negate(self) -> Result<Self, EvalError> {
+        Ok(-self)
+    }
+
+    fn apply(op: BinOp, a: Self, b: Self) -> Result<Self, EvalError> {
+        match op {
+            BinOp::Add => Ok(a + b),
+            BinOp::Sub => Ok(a - b),
+            BinOp::Mul => Ok(a * b),
+            BinOp::Div if b.value == 0 => Err(EvalError::DivisionByZero),
+            BinOp::Div => Ok(a * b.inverse().ok_or(EvalError::Undefined(op))?),
+            BinOp::Rem if b.value == 0 => Err(EvalError::DivisionByZero),
+            BinOp::Rem if a.modulus.checked_rem(b.value) == Some(0) => {
+                Ok(a.with_value((a.value % b.value) as u128))
+            }
+            BinOp::Rem => Err(EvalError::Undefined(op)),
+        }
+    }
+}
+
+#[test]
+fn test_modular_arithmetic() {
+    let m = |v| ModularInt::new(v, 7);
+    assert_eq!(m(3), m(5) + m(5));
+    assert_eq!(m(4), m(1) - m(4));
+    assert_eq!(m(6), m(-1));
+    assert_eq!(m(1), m(3) * m(5));
+    assert_eq!(m(2), -m(5));
+    assert_eq!(m(1), m(3).pow(6));
+    assert_eq!(Some(m(5)), m(3).inverse());
+    assert_eq!(None, ModularInt::new(4, 8).inverse());
+
+    let big = ModularInt::new(i64::MAX, u64::MAX - 58);
+    assert_eq!(
+        ((i64::MAX as u128).pow(2) % (u64::MAX - 58) as u128) as u64,
+        (big * big).value()
+    );
+}
+
+#[test]
+fn test_modular_divisibility() {
+    let modulus = 2 * 3 * 5;
+    let x = ModularInt::new(12, modulus);
+    assert!(x.is_divisible_by(3));
+    assert!(!x.is_divisible_by(5));
+    let x = x * x * x;
+    assert!(x.is_divisible_by(2));
+    assert!(!x.is_divisible_by(5));
+}
+
+#[test]
+#[should_panic(expected = "does not divide the modulus")]
+fn test_modular_divisibility_unknown() {
+    ModularInt::new(12, 30).is_divisible_by(7);
+}
+
+#[test]
+fn test_modular_expr() {
+    use crate::expr::Expr;
+
+    let expr = "old * old + 3".parse::<Expr>().unwrap();
+    let old = ModularInt::new(79, 96577);
+    let new = expr.eval_in(&96577, &|_| Some(old)).unwrap();
+    assert_eq!(ModularInt::new(79 * 79 + 3, 96577), new);
+
+    let halve = "x / 2".parse::<Expr>().unwrap();
+    let x = ModularInt::new(3, 7);
+    assert_eq!(Ok(ModularInt::new(5, 7)), halve.eval_in(&7, &|_| Some(x)));
+    let x = ModularInt::new(3, 8);
+    assert_eq!(
+        Err(EvalError::Undefined(BinOp::Div)),
+        halve.eval_in(&8, &|_| Some(x))
+    );
+}
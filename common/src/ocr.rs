@@ -0,0 +1,285 @@
+use crate::charcanvas::CharCanvas;
+use crate::grid2d::Grid2d;
+use crate::vec2i::Point;
+use std::fmt::{Display, Formatter};
+
+/// The block letter fonts puzzles draw their answers in.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Font {
+    /// 4 by 6 pixel letters, 5 pixels apart.
+    Small,
+    /// 6 by 10 pixel letters, 8 pixels apart.
+    Large,
+}
+
+impl Font {
+    #[inline]
+    pub const fn height(&self) -> i32 {
+        match self {
+            Font::Small => 6,
+            Font::Large => 10,
+        }
+    }
+
+    #[inline]
+    pub const fn pitch(&self) -> i32 {
+        match self {
+            Font::Small => 5,
+            Font::Large => 8,
+        }
+    }
+
+    pub fn for_height(height: i32) -> Option<Font> {
+        [Font::Small, Font::Large]
+            .into_iter()
+            .find(|font| font.height() == height)
+    }
+
+    fn glyphs(&self) -> &'static [(char, &'static str)] {
+        match self {
+            Font::Small => &SMALL_GLYPHS,
+            Font::Large => &LARGE_GLYPHS,
+        }
+    }
+
+    fn recognize(&self, pixels: &str) -> Option<char> {
+        self.glyphs()
+            .iter()
+            .find(|(_, glyph)| canonical(glyph) == pixels)
+            .map(|(letter, _)| *letter)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OcrError {
+    /// Nothing is lit, or the lit pixels are not as high as any font.
+    UnsupportedHeight(i32),
+    /// The text as far as it could be read, with `?` for every glyph that matches no letter,
+    /// and the top left corners of those glyphs.
+    UnknownGlyphs { text: String, positions: Vec<Point> },
+}
+
+impl Display for OcrError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OcrError::UnsupportedHeight(height) => {
+                write!(f, "no font is {} pixels high", height)
+            }
+            OcrError::UnknownGlyphs { text, positions } => {
+                write!(f, "unrecognized glyphs in {:?} at", text)?;
+                for p in positions {
+                    write!(f, " ({}, {})", p.x(), p.y())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+/// Reads the letters drawn with `#` on `canvas`.
+pub fn read_canvas(canvas: &CharCanvas) -> Result<String, OcrError> {
+    read(canvas, |c| *c == '#')
+}
+
+/// Reads the letters drawn by the tiles `is_lit` accepts. The font is picked by the height of
+/// the lit pixels, and the first letter is expected at the left edge of the grid.
+pub fn read<T>(grid: &Grid2d<T>, is_lit: impl Fn(&T) -> bool) -> Result<String, OcrError> {
+    let lit = grid.points_where(&is_lit).collect::<Vec<_>>();
+    let top = lit.iter().map(|p| p.y()).min().unwrap_or(0);
+    let bottom = lit.iter().map(|p| p.y()).max().unwrap_or(-1);
+    let right = lit.iter().map(|p| p.x()).max().unwrap_or(-1);
+    let height = bottom - top + 1;
+    let font = Font::for_height(height).ok_or(OcrError::UnsupportedHeight(height))?;
+
+    let left = grid.bounds.left();
+    let mut text = String::new();
+    let mut positions = vec![];
+    for x in (left..=right).step_by(font.pitch() as usize) {
+        let corner = Point::new(x, top);
+        let rows = (0..font.height()).map(|dy| {
+            (0..font.pitch())
+                .map(|dx| {
+                    if is_lit(&grid[corner + [dx, dy]]) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>()
+        });
+        let pixels = canonical(&rows.collect::<Vec<_>>().join("\n"));
+        if pixels.chars().all(|c| c != '#') {
+            text.push(' ');
+        } else if let Some(letter) = font.recognize(&pixels) {
+            text.push(letter);
+        } else {
+            text.push('?');
+            positions.push(corner);
+        }
+    }
+
+    if positions.is_empty() {
+        Ok(text)
+    } else {
+        Err(OcrError::UnknownGlyphs { text, positions })
+    }
+}
+
+/// Strips the unlit pixels at the end of each row, so glyphs of different widths compare
+/// equal regardless of the spacing around them.
+fn canonical(glyph: &str) -> String {
+    glyph
+        .lines()
+        .map(|row| row.trim_end_matches('.'))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+const SMALL_GLYPHS: [(char, &str); 18] = [
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', ".###\n..#.\n..#.\n..#.\n..#.\n.###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+const LARGE_GLYPHS: [(char, &str); 15] = [
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+#[test]
+fn test_read_small() {
+    let canvas = CharCanvas::from_text(
+        '.',
+        [0, 0],
+        "\
+#..#.####.#....#.....##..
+#..#.#....#....#....#..#.
+####.###..#....#....#..#.
+#..#.#....#....#....#..#.
+#..#.#....#....#....#..#.
+#..#.####.####.####..##..
+",
+    );
+    assert_eq!(Ok("HELLO".to_string()), read_canvas(&canvas));
+}
+
+#[test]
+fn test_read_large() {
+    let glyphs = ['L', 'A', 'R', 'G', 'E'].map(|letter| {
+        let (_, glyph) = LARGE_GLYPHS.iter().find(|(l, _)| *l == letter).unwrap();
+        glyph.lines().collect::<Vec<_>>()
+    });
+    let rows = (0..10)
+        .map(|y| {
+            glyphs
+                .iter()
+                .map(|glyph| glyph[y])
+                .collect::<Vec<_>>()
+                .join("..")
+        })
+        .collect::<Vec<_>>();
+    let canvas = CharCanvas::from_text('.', [0, 0], &rows.join("\n"));
+    assert_eq!(Ok("LARGE".to_string()), read(&canvas, |c| *c == '#'));
+}
+
+#[test]
+fn test_read_unknown() {
+    let canvas = CharCanvas::from_text(
+        '.',
+        [0, 0],
+        "\
+.##..###..###.
+#..#.#..#.#..#
+#..#.###..#..#
+####.#..#.#..#
+#..#.#..#.#..#
+#..#.###..###.
+",
+    );
+    assert_eq!(
+        Err(OcrError::UnknownGlyphs {
+            text: "AB?".to_string(),
+            positions: vec![Point::new(10, 0)]
+        }),
+        read_canvas(&canvas)
+    );
+    assert_eq!(
+        Err(OcrError::UnsupportedHeight(0)),
+        read_canvas(&CharCanvas::with_size('.', [5, 6]))
+    );
+}
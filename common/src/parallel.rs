@@ -0,0 +1,145 @@
+use std::cmp::min;
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Runs a function over a range of indices on scoped worker threads. The range is split into
+/// chunks, and each worker takes the next unclaimed chunk until none are left.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Parallel {
+    threads: usize,
+    chunk_size: usize,
+}
+
+impl Default for Parallel {
+    /// As many threads as the machine runs in parallel, taking chunks of 256 indices.
+    fn default() -> Self {
+        Self {
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            chunk_size: 256,
+        }
+    }
+}
+
+impl Parallel {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn threads(mut self, threads: usize) -> Self {
+        assert!(threads > 0, "at least one thread is needed");
+        self.threads = threads;
+        self
+    }
+
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunks can't be empty");
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    fn workers(&self, range: &Range<usize>) -> usize {
+        min(self.threads, range.len())
+    }
+
+    fn next_chunk(&self, next: &AtomicUsize, end: usize) -> Option<Range<usize>> {
+        let start = next.fetch_add(self.chunk_size, Ordering::Relaxed);
+        (start < end).then(|| start..min(start.saturating_add(self.chunk_size), end))
+    }
+
+    /// Calls `f` for every index in `range`, returning the results in index order.
+    pub fn map<T: Send>(&self, range: Range<usize>, f: impl Fn(usize) -> T + Sync) -> Vec<T> {
+        let next = AtomicUsize::new(range.start);
+        let mut chunks = thread::scope(|scope| {
+            let workers = (0..self.workers(&range))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = vec![];
+                        while let Some(chunk) = self.next_chunk(&next, range.end) {
+                            done.push((chunk.start, chunk.map(&f).collect::<Vec<_>>()));
+                        }
+                        done
+                    })
+                })
+                .collect::<Vec<_>>();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        chunks.sort_unstable_by_key(|(start, _)| *start);
+        chunks
+            .into_iter()
+            .flat_map(|(_, results)| results)
+            .collect()
+    }
+
+    /// The result for the lowest index in `range` that `f` returns one for, like
+    /// [`Iterator::find_map`]. Once any worker finds a result, no index above it is tried.
+    pub fn find_map<T: Send>(
+        &self,
+        range: Range<usize>,
+        f: impl Fn(usize) -> Option<T> + Sync,
+    ) -> Option<T> {
+        let next = AtomicUsize::new(range.start);
+        let found = AtomicUsize::new(usize::MAX);
+        thread::scope(|scope| {
+            let workers = (0..self.workers(&range))
+                .map(|_| {
+                    scope.spawn(|| {
+                        while let Some(chunk) = self.next_chunk(&next, range.end) {
+                            let result = chunk
+                                .take_while(|&i| i < found.load(Ordering::Relaxed))
+                                .find_map(|i| f(i).map(|result| (i, result)));
+                            if let Some((i, _)) = result {
+                                // Chunks are handed out in order, so later ones can't do better.
+                                found.fetch_min(i, Ordering::Relaxed);
+                                return result;
+                            }
+                        }
+                        None
+                    })
+                })
+                .collect::<Vec<_>>();
+            workers
+                .into_iter()
+                .filter_map(|worker| worker.join().unwrap())
+                .min_by_key(|(i, _)| *i)
+                .map(|(_, result)| result)
+        })
+    }
+}
+
+#[test]
+fn test_parallel_map() {
+    let squares = (0..1000).map(|i| i * i).collect::<Vec<_>>();
+    for threads in [1, 3, 8] {
+        let parallel = Parallel::new().threads(threads).chunk_size(7);
+        assert_eq!(squares, parallel.map(0..1000, |i| i * i));
+    }
+    assert_eq!(vec![5, 6], Parallel::new().map(5..7, |i| i));
+    assert!(Parallel::new().map(3..3, |i| i).is_empty());
+}
+
+#[test]
+fn test_parallel_find_map() {
+    let parallel = Parallel::new().threads(4).chunk_size(10);
+    let square_above = |i: usize| (i * i > 2000).then_some(i);
+    assert_eq!(Some(45), parallel.find_map(0..10_000, square_above));
+    assert_eq!(Some(50), parallel.find_map(50..10_000, square_above));
+    assert_eq!(None, parallel.find_map(1..45, square_above));
+    assert_eq!(None, parallel.find_map(0..0, square_above));
+}
+
+#[test]
+fn test_parallel_find_map_stops_early() {
+    let calls = AtomicUsize::new(0);
+    let parallel = Parallel::new().threads(1).chunk_size(100);
+    let result = parallel.find_map(0..1_000_000, |i| {
+        calls.fetch_add(1, Ordering::Relaxed);
+        (i == 5).then_some(i)
+    });
+    assert_eq!(Some(5), result);
+    assert_eq!(6, calls.into_inner());
+}
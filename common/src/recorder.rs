@@ -0,0 +1,378 @@
+use crate::ansi::CLEAR_SCREEN;
+use crate::charcanvas::CharCanvas;
+use crate::grid2d::Grid2d;
+use crate::image::{Image, Rgb};
+use crate::vec2i::Bounds;
+#[cfg(test)]
+use crate::vec2i::Point;
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::Duration;
+
+/// Collects snapshots of a simulation as it runs, to replay them in a terminal or save them as
+/// an animated GIF afterwards.
+#[derive(Clone)]
+pub struct Recorder {
+    frames: Vec<CharCanvas>,
+    interval: usize,
+    until_next: usize,
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::every(1)
+    }
+}
+
+impl Recorder {
+    /// A recorder that keeps one frame out of every `interval` passed to [`Recorder::record`].
+    pub fn every(interval: usize) -> Self {
+        assert!(interval > 0);
+        Self {
+            frames: vec![],
+            interval,
+            until_next: 0,
+        }
+    }
+
+    #[inline]
+    pub fn frames(&self) -> &[CharCanvas] {
+        &self.frames
+    }
+
+    /// Counts a step, and captures `canvas` if the step is one to keep.
+    pub fn record(&mut self, canvas: &CharCanvas) {
+        self.record_with(canvas, |c| *c)
+    }
+
+    pub fn record_with<T>(&mut self, grid: &Grid2d<T>, tile: impl Fn(&T) -> char) {
+        if self.until_next == 0 {
+            self.capture_with(grid, tile);
+            self.until_next = self.interval;
+        }
+        self.until_next -= 1;
+    }
+
+    /// Captures `canvas` regardless of the interval, e.g. to always include the final state.
+    pub fn capture(&mut self, canvas: &CharCanvas) {
+        self.frames.push(canvas.clone());
+    }
+
+    pub fn capture_with<T>(&mut self, grid: &Grid2d<T>, tile: impl Fn(&T) -> char) {
+        let empty = tile(&grid.empty);
+        let tiles = grid.iter().map(|(_, t)| tile(t)).collect();
+        self.frames
+            .push(CharCanvas::from_parts(empty, grid.bounds, tiles));
+    }
+
+    /// The bounds covering every frame, so that frames of grids that grew line up.
+    fn bounds(&self) -> Bounds {
+        self.frames.iter().fold(Bounds::EMPTY, |bounds, frame| {
+            bounds.extend_to_bounds(&frame.bounds)
+        })
+    }
+
+    /// Plays the frames back by redrawing them in place, waiting `delay` between frames.
+    pub fn replay(&self, w: &mut impl Write, delay: Duration) -> std::io::Result<()> {
+        let bounds = self.bounds();
+        for frame in &self.frames {
+            write!(w, "{}{}", CLEAR_SCREEN, frame.view(bounds))?;
+            w.flush()?;
+            std::thread::sleep(delay);
+        }
+        Ok(())
+    }
+
+    /// Writes the frames as a looping animated GIF, each tile drawn as a `scale` by `scale`
+    /// square in the color `color` picks. At most 256 different colors can be used.
+    pub fn write_gif(
+        &self,
+        w: &mut impl Write,
+        scale: usize,
+        delay: Duration,
+        color: impl Fn(char) -> Rgb,
+    ) -> std::io::Result<()> {
+        let bounds = self.bounds();
+        let images = self
+            .frames
+            .iter()
+            .map(|frame| Image::from_grid(&frame.view(bounds).to_grid(), scale, |c| color(*c)))
+            .collect::<Vec<_>>();
+
+        let mut palette = Vec::<Rgb>::new();
+        let mut palette_index = HashMap::new();
+        let frames = images
+            .iter()
+            .map(|image| {
+                image
+                    .pixels()
+                    .iter()
+                    .map(|pixel| {
+                        *palette_index.entry(*pixel).or_insert_with(|| {
+                            palette.push(*pixel);
+                            palette.len() - 1
+                        }) as u8
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        if palette.len() > 256 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("{} colors, but a GIF can only have 256", palette.len()),
+            ));
+        }
+
+        let (width, height) = images
+            .first()
+            .map_or((0, 0), |image| (image.width(), image.height()));
+        let too_large = |_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("{}x{} pixels is too large for a GIF", width, height),
+            )
+        };
+        let width = u16::try_from(width).map_err(too_large)?;
+        let height = u16::try_from(height).map_err(too_large)?;
+        let palette_bits = (usize::BITS - (palette.len().max(2) - 1).leading_zeros()) as u8;
+        palette.resize(1 << palette_bits, [0, 0, 0]);
+
+        w.write_all(b"GIF89a")?;
+        w.write_all(&width.to_le_bytes())?;
+        w.write_all(&height.to_le_bytes())?;
+        // Global color table, 8 bits per color, with 2^palette_bits entries
+        w.write_all(&[0xf0 | (palette_bits - 1), 0, 0])?;
+        w.write_all(&palette.concat())?;
+        // Loop forever
+        w.write_all(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00")?;
+
+        let centiseconds = (delay.as_millis() / 10) as u16;
+        let min_code_size = palette_bits.max(2);
+        for pixels in frames {
+            w.write_all(&[0x21, 0xf9, 0x04, 0x00])?;
+            w.write_all(&centiseconds.to_le_bytes())?;
+            w.write_all(&[0x00, 0x00])?;
+
+            w.write_all(&[0x2c, 0, 0, 0, 0])?;
+            w.write_all(&width.to_le_bytes())?;
+            w.write_all(&height.to_le_bytes())?;
+            w.write_all(&[0x00, min_code_size])?;
+            for block in lzw_encode(&pixels, min_code_size).chunks(255) {
+                w.write_all(&[block.len() as u8])?;
+                w.write_all(block)?;
+            }
+            w.write_all(&[0x00])?;
+        }
+        w.write_all(&[0x3b])
+    }
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u8) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// GIF flavored LZW: variable code sizes up to 12 bits, least significant bit first, starting
+/// over with a clear code whenever the table is full.
+fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    const MAX_CODE: u16 = 4096;
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+    let mut out = BitWriter {
+        bytes: vec![],
+        buffer: 0,
+        bits: 0,
+    };
+    let mut table = HashMap::<(u16, u8), u16>::new();
+    let mut next_code = end + 1;
+    let mut code_size = min_code_size + 1;
+
+    out.write(clear, code_size);
+    let mut prefix = None;
+    for &index in indices {
+        let Some(code) = prefix else {
+            prefix = Some(index as u16);
+            continue;
+        };
+        if let Some(&extended) = table.get(&(code, index)) {
+            prefix = Some(extended);
+            continue;
+        }
+        out.write(code, code_size);
+        if next_code < MAX_CODE {
+            table.insert((code, index), next_code);
+            next_code += 1;
+            if next_code > 1 << code_size && code_size < 12 {
+                code_size += 1;
+            }
+        } else {
+            out.write(clear, code_size);
+            table.clear();
+            next_code = end + 1;
+            code_size = min_code_size + 1;
+        }
+        prefix = Some(index as u16);
+    }
+    if let Some(code) = prefix {
+        out.write(code, code_size);
+    }
+    out.write(end, code_size);
+    out.finish()
+}
+
+#[cfg(test)]
+fn lzw_decode(data: &[u8], min_code_size: u8) -> Vec<u8> {
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+    let mut table = Vec::<Vec<u8>>::new();
+    let mut code_size = min_code_size + 1;
+    let mut previous: Option<u16> = None;
+    let mut out = vec![];
+    let (mut buffer, mut bits) = (0u32, 0u8);
+    let mut bytes = data.iter();
+    loop {
+        while bits < code_size {
+            buffer |= (*bytes.next().unwrap() as u32) << bits;
+            bits += 8;
+        }
+        let code = (buffer & ((1 << code_size) - 1)) as u16;
+        buffer >>= code_size;
+        bits -= code_size;
+
+        if code == clear {
+            table = (0..clear).map(|i| vec![i as u8]).collect();
+            table.extend([vec![], vec![]]);
+            code_size = min_code_size + 1;
+            previous = None;
+        } else if code == end {
+            return out;
+        } else {
+            let entry = match previous {
+                Some(previous) => {
+                    let entry = table.get(code as usize).cloned().unwrap_or_else(|| {
+                        let previous = &table[previous as usize];
+                        [&previous[..], &previous[..1]].concat()
+                    });
+                    if table.len() < 4096 {
+                        let previous = &table[previous as usize];
+                        table.push([&previous[..], &entry[..1]].concat());
+                        if table.len() == 1 << code_size && code_size < 12 {
+                            code_size += 1;
+                        }
+                    }
+                    entry
+                }
+                None => table[code as usize].clone(),
+            };
+            out.extend(&entry);
+            previous = Some(code);
+        }
+    }
+}
+
+#[test]
+fn test_lzw_round_trip() {
+    let patterned = (0..20_000u32)
+        .map(|i| ((i * 7 + i / 13) % 5) as u8)
+        .collect::<Vec<_>>();
+    assert_eq!(patterned, lzw_decode(&lzw_encode(&patterned, 3), 3));
+
+    let noisy = (0..50_000u32)
+        .map(|i| (i.wrapping_mul(2654435761) >> 24) as u8)
+        .collect::<Vec<_>>();
+    assert_eq!(noisy, lzw_decode(&lzw_encode(&noisy, 8), 8));
+
+    let flat = vec![1; 100_000];
+    assert_eq!(flat, lzw_decode(&lzw_encode(&flat, 2), 2));
+    assert_eq!(Vec::<u8>::new(), lzw_decode(&lzw_encode(&[], 2), 2));
+}
+
+#[test]
+fn test_recorder_interval() {
+    let mut recorder = Recorder::every(3);
+    let mut canvas = CharCanvas::new('.');
+    for x in 0..7 {
+        canvas[Point::new(x, 0)] = '#';
+        recorder.record(&canvas);
+    }
+    recorder.capture(&canvas);
+    let frames = recorder
+        .frames()
+        .iter()
+        .map(|frame| frame.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["#\n", "####\n", "#######\n", "#######\n"], frames);
+}
+
+#[test]
+fn test_recorder_replay() {
+    let mut recorder = Recorder::default();
+    let mut grid = Grid2d::new(false);
+    grid[Point::new(0, 0)] = true;
+    recorder.record_with(&grid, |on| if *on { '#' } else { '.' });
+    grid[Point::new(1, 1)] = true;
+    recorder.record_with(&grid, |on| if *on { '#' } else { '.' });
+
+    let mut out = vec![];
+    recorder.replay(&mut out, Duration::ZERO).unwrap();
+    assert_eq!(
+        "\x1b[H\x1b[2J#.\n..\n\x1b[H\x1b[2J#.\n.#\n",
+        String::from_utf8(out).unwrap()
+    );
+}
+
+#[test]
+fn test_recorder_gif() {
+    let mut recorder = Recorder::default();
+    let mut canvas = CharCanvas::with_size('.', [3, 2]);
+    recorder.record(&canvas);
+    canvas[Point::new(2, 1)] = '#';
+    recorder.record(&canvas);
+
+    let mut gif = vec![];
+    recorder
+        .write_gif(&mut gif, 2, Duration::from_millis(100), |c| match c {
+            '#' => [255, 255, 255],
+            _ => [0, 0, 64],
+        })
+        .unwrap();
+    assert_eq!(b"GIF89a\x06\x00\x04\x00\xf0\x00\x00", &gif[..13]);
+    assert_eq!(&[0, 0, 64, 255, 255, 255], &gif[13..19]);
+    assert_eq!(Some(&0x3b), gif.last());
+    assert_eq!(
+        2,
+        gif.windows(4).filter(|w| w == b"\x21\xf9\x04\x00").count()
+    );
+}
+
+#[test]
+fn test_recorder_gif_too_large() {
+    let mut recorder = Recorder::default();
+    recorder.record(&CharCanvas::with_size('.', [40_000, 1]));
+    let mut gif = vec![];
+    let error = recorder
+        .write_gif(&mut gif, 2, Duration::ZERO, |_| [0, 0, 0])
+        .unwrap_err();
+    assert_eq!(std::io::ErrorKind::InvalidInput, error.kind());
+    assert!(gif.is_empty());
+}
@@ -0,0 +1,201 @@
+use crate::grid2d::Grid2d;
+use crate::vec2i::{Bounds, Point};
+use std::fmt::{Display, Formatter};
+
+/// Renders a grid as text, with control over how each tile is shown. Build one with
+/// [`Grid2d::renderer`] or [`Grid2d::render_with`] and print it with `Display`.
+pub struct Renderer<'a, T> {
+    grid: &'a Grid2d<T>,
+    bounds: Bounds,
+    format: Box<dyn Fn(&T) -> String + 'a>,
+    width: usize,
+    ruler_step: Option<i32>,
+    markers: Vec<(Point, String)>,
+    legend: Vec<(String, String)>,
+}
+
+impl<T: Display> Grid2d<T> {
+    pub fn renderer(&self) -> Renderer<'_, T> {
+        self.render_with(|tile| tile.to_string())
+    }
+}
+
+impl<T> Grid2d<T> {
+    pub fn render_with<'a>(&'a self, format: impl Fn(&T) -> String + 'a) -> Renderer<'a, T> {
+        Renderer {
+            grid: self,
+            bounds: self.bounds,
+            format: Box::new(format),
+            width: 1,
+            ruler_step: None,
+            markers: vec![],
+            legend: vec![],
+        }
+    }
+}
+
+impl<T> Renderer<'_, T> {
+    /// Renders `bounds` instead of the grid's bounds. Points outside the grid show as empty.
+    pub fn bounds(mut self, bounds: Bounds) -> Self {
+        self.bounds = bounds;
+        self
+    }
+
+    /// Right-aligns every tile in a column this many characters wide.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Labels every row with its y coordinate, and writes x coordinates vertically above the
+    /// first and last column and every column where x is a multiple of `step`, which must be
+    /// positive.
+    pub fn axes(mut self, step: i32) -> Self {
+        assert!(step > 0, "axis step must be positive, got {}", step);
+        self.ruler_step = Some(step);
+        self
+    }
+
+    /// Shows `marker` instead of the tile at `p`.
+    pub fn marker(mut self, p: Point, marker: impl Display) -> Self {
+        self.markers.push((p, marker.to_string()));
+        self
+    }
+
+    /// Adds a line explaining `symbol` below the grid.
+    pub fn legend(mut self, symbol: impl Display, description: impl Display) -> Self {
+        self.legend
+            .push((symbol.to_string(), description.to_string()));
+        self
+    }
+
+    fn tile(&self, p: Point) -> String {
+        self.markers
+            .iter()
+            .rev()
+            .find(|(marker_point, _)| *marker_point == p)
+            .map(|(_, marker)| marker.clone())
+            .unwrap_or_else(|| (self.format)(&self.grid[p]))
+    }
+
+    fn fmt_ruler(&self, f: &mut Formatter<'_>, step: i32, margin: usize) -> std::fmt::Result {
+        let labels = (self.bounds.left()..=self.bounds.right())
+            .map(|x| {
+                let labelled = x == self.bounds.left() || x == self.bounds.right() || x % step == 0;
+                if labelled {
+                    x.to_string()
+                } else {
+                    String::new()
+                }
+            })
+            .collect::<Vec<_>>();
+        let height = labels.iter().map(String::len).max().unwrap_or(0);
+        for line in 0..height {
+            write!(f, "{:margin$}", "", margin = margin)?;
+            for label in &labels {
+                let c = (line + label.len())
+                    .checked_sub(height)
+                    .and_then(|i| label.chars().nth(i))
+                    .unwrap_or(' ');
+                write!(f, "{:>width$}", c, width = self.width)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Display for Renderer<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.bounds.is_empty() {
+            let rows = self.bounds.top()..=self.bounds.bottom();
+            let margin = self.ruler_step.map_or(0, |_| {
+                let label_width = rows.clone().map(|y| y.to_string().len()).max();
+                label_width.unwrap_or(0) + 1
+            });
+            if let Some(step) = self.ruler_step {
+                self.fmt_ruler(f, step, margin)?;
+            }
+            for y in rows {
+                if margin > 0 {
+                    write!(f, "{:>width$} ", y, width = margin - 1)?;
+                }
+                for x in self.bounds.left()..=self.bounds.right() {
+                    let tile = self.tile(Point::new(x, y));
+                    write!(f, "{:>width$}", tile, width = self.width)?;
+                }
+                writeln!(f)?;
+            }
+        }
+        for (symbol, description) in &self.legend {
+            writeln!(f, "{}: {}", symbol, description)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_renderer_default() {
+    let mut grid = Grid2d::new('.');
+    grid[Point::new(0, 0)] = '#';
+    grid[Point::new(1, 1)] = '#';
+    assert_eq!(grid.to_string(), grid.renderer().to_string());
+}
+
+#[test]
+fn test_renderer_format_and_width() {
+    let mut costs = Grid2d::with_size(i32::MAX, [3, 2]);
+    costs[Point::new(0, 0)] = 0;
+    costs[Point::new(1, 0)] = 12;
+    costs[Point::new(2, 1)] = 7;
+    let rendered = costs
+        .render_with(|cost| match cost {
+            &i32::MAX => "-".to_string(),
+            cost => cost.to_string(),
+        })
+        .width(3)
+        .to_string();
+    assert_eq!("  0 12  -\n  -  -  7\n", rendered);
+}
+
+#[test]
+fn test_renderer_axes() {
+    let grid = Grid2d::with_bounds('.', Bounds::new(-1, 10, 1, 8));
+    assert_eq!(
+        "     1\n   8 0\n-1 ...\n 0 ...\n 1 ...\n",
+        grid.renderer().axes(5).to_string()
+    );
+
+    let grid = Grid2d::with_bounds('.', Bounds::new(9, 1, 10, -1));
+    assert_eq!(
+        "   -  \n   101\n 9 ...\n10 ...\n",
+        grid.renderer().axes(5).to_string()
+    );
+}
+
+#[test]
+#[should_panic(expected = "axis step must be positive")]
+fn test_renderer_axes_zero_step() {
+    Grid2d::new('.').renderer().axes(0);
+}
+
+#[test]
+fn test_renderer_markers_and_legend() {
+    let grid = Grid2d::with_size('.', [4, 1]);
+    let rendered = grid
+        .renderer()
+        .marker(Point::new(0, 0), 'S')
+        .marker(Point::new(3, 0), 'E')
+        .legend('S', "start")
+        .legend('E', "end")
+        .to_string();
+    assert_eq!("S..E\nS: start\nE: end\n", rendered);
+
+    assert_eq!(
+        "..\n.S\n",
+        grid.renderer()
+            .bounds(Bounds::new(-1, 1, 0, 0))
+            .marker(Point::new(1, 0), 'S')
+            .to_string()
+    );
+}
@@ -0,0 +1,174 @@
+use crate::diamond::Diamond;
+use crate::vec2i::{Bounds, Point};
+
+/// Maps `(x, y)` to `(u, v) = (x + y, x - y)`, which turns Manhattan distance into Chebyshev
+/// distance and Manhattan diamonds into axis-aligned squares.
+///
+/// The mapping doubles the lattice: only rotated points where `u` and `v` have the same parity
+/// map back to integer points.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Rotate45;
+
+impl Rotate45 {
+    #[inline]
+    pub const fn forward(&self, p: &Point) -> Point {
+        Point::new(p.x() + p.y(), p.x() - p.y())
+    }
+
+    #[inline]
+    pub const fn is_lattice_point(&self, p: &Point) -> bool {
+        (p.x() - p.y()) % 2 == 0
+    }
+
+    #[inline]
+    pub const fn inverse(&self, p: &Point) -> Option<Point> {
+        if self.is_lattice_point(p) {
+            Some(Point::new((p.x() + p.y()) / 2, (p.x() - p.y()) / 2))
+        } else {
+            None
+        }
+    }
+
+    /// The smallest rotated bounds containing every rotated point of `b`.
+    #[inline]
+    pub fn forward_bounds(&self, b: &Bounds) -> Bounds {
+        if b.is_empty() {
+            return Bounds::EMPTY;
+        }
+        Bounds::new(
+            b.left() - b.bottom(),
+            b.right() + b.bottom(),
+            b.right() - b.top(),
+            b.left() + b.top(),
+        )
+    }
+
+    /// The smallest bounds containing every lattice point of the rotated bounds `b`.
+    pub fn inverse_bounds(&self, b: &Bounds) -> Bounds {
+        if b.is_empty() {
+            return Bounds::EMPTY;
+        }
+        let left = ceil_half(b.left() + b.top());
+        let right = (b.right() + b.bottom()).div_euclid(2);
+        let top = ceil_half(b.left() - b.bottom());
+        let bottom = (b.right() - b.top()).div_euclid(2);
+        if left > right || top > bottom {
+            Bounds::EMPTY
+        } else {
+            Bounds::new(top, right, bottom, left)
+        }
+    }
+
+    /// The lattice points of the rotated bounds `b`, mapped back to unrotated coordinates.
+    pub fn lattice_points(&self, b: &Bounds) -> impl Iterator<Item = Point> + '_ {
+        b.iter_points().filter_map(|p| self.inverse(&p))
+    }
+
+    #[inline]
+    pub const fn diamond_to_square(&self, d: &Diamond) -> Bounds {
+        let center = self.forward(&d.center());
+        let radius = d.radius();
+        Bounds::new(
+            center.y() - radius,
+            center.x() + radius,
+            center.y() + radius,
+            center.x() - radius,
+        )
+    }
+
+    /// The diamond whose rotation is exactly the square `b`, if there is one. The square must
+    /// have an odd side length and a center on the lattice.
+    pub fn square_to_diamond(&self, b: &Bounds) -> Option<Diamond> {
+        let [width, height]: [i32; 2] = b.size().into();
+        if b.is_empty() || width != height || width % 2 == 0 {
+            return None;
+        }
+        let center = Point::new((b.left() + b.right()) / 2, (b.top() + b.bottom()) / 2);
+        self.inverse(&center)
+            .map(|center| Diamond::new(center, width / 2))
+    }
+}
+
+#[inline]
+const fn ceil_half(v: i32) -> i32 {
+    -(-v).div_euclid(2)
+}
+
+#[test]
+fn test_rotate45_round_trip() {
+    for p in Bounds::new(-3, 3, 3, -3).iter_points() {
+        let rotated = Rotate45.forward(&p);
+        assert!(Rotate45.is_lattice_point(&rotated));
+        assert_eq!(Some(p), Rotate45.inverse(&rotated));
+    }
+    assert_eq!(None, Rotate45.inverse(&Point::new(1, 0)));
+    assert_eq!(None, Rotate45.inverse(&Point::new(-2, 3)));
+}
+
+#[test]
+fn test_rotate45_manhattan_is_chebyshev() {
+    let a = Point::new(3, -7);
+    for b in Bounds::new(-10, 10, 10, -10).iter_points() {
+        assert_eq!(
+            a.manhattan_distance(&b),
+            Rotate45
+                .forward(&a)
+                .chebyshev_distance(&Rotate45.forward(&b))
+        );
+    }
+}
+
+#[test]
+fn test_rotate45_bounds() {
+    let b = Bounds::new(-1, 3, 2, 1);
+    let rotated = Rotate45.forward_bounds(&b);
+    assert!(b
+        .iter_points()
+        .all(|p| rotated.contains(&Rotate45.forward(&p))));
+    assert_eq!(Bounds::new(-1, 5, 4, 0), rotated);
+
+    assert!(Rotate45
+        .inverse_bounds(&Rotate45.forward_bounds(&b))
+        .contains_bounds(&b));
+    assert_eq!(
+        Bounds::EMPTY,
+        Rotate45.inverse_bounds(&Bounds::point(Point::new(0, 1)))
+    );
+    assert_eq!(
+        Bounds::new(0, 1, 0, 0),
+        Rotate45.inverse_bounds(&Bounds::new(0, 1, 1, 0))
+    );
+    assert_eq!(Bounds::EMPTY, Rotate45.forward_bounds(&Bounds::EMPTY));
+}
+
+#[test]
+fn test_rotate45_lattice_points() {
+    let square = Bounds::new(0, 2, 2, 0);
+    let points = Rotate45.lattice_points(&square).collect::<Vec<_>>();
+    assert_eq!(5, points.len());
+    assert!(points.contains(&Point::new(1, 0)));
+    assert!(points.contains(&Point::new(0, 0)));
+    assert!(points.contains(&Point::new(2, 0)));
+    assert!(points.contains(&Point::new(1, 1)));
+    assert!(points.contains(&Point::new(1, -1)));
+}
+
+#[test]
+fn test_rotate45_diamonds() {
+    let diamond = Diamond::new(Point::new(-4, 2), 3);
+    let square = Rotate45.diamond_to_square(&diamond);
+    assert_eq!(Bounds::new(-9, 1, -3, -5), square);
+    assert_eq!(Some(diamond), Rotate45.square_to_diamond(&square));
+    assert_eq!(
+        diamond
+            .bounds()
+            .iter_points()
+            .filter(|p| diamond.contains(p))
+            .count(),
+        Rotate45.lattice_points(&square).count()
+    );
+
+    assert_eq!(None, Rotate45.square_to_diamond(&Bounds::new(0, 1, 1, 0)));
+    assert_eq!(None, Rotate45.square_to_diamond(&Bounds::new(0, 2, 1, 0)));
+    assert_eq!(None, Rotate45.square_to_diamond(&Bounds::new(0, 3, 2, 1)));
+}
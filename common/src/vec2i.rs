@@ -45,6 +45,11 @@ impl Point {
     pub fn manhattan_distance(&self, other: &Point) -> i32 {
         self.vector(other).manhattan_len()
     }
+
+    #[inline]
+    pub fn chebyshev_distance(&self, other: &Point) -> i32 {
+        self.vector(other).chebyshev_len()
+    }
 }
 
 impl From<Point> for [i32; 2] {
@@ -174,6 +179,47 @@ impl Vector {
     pub fn manhattan_len(&self) -> i32 {
         self.0.abs().reduce_sum()
     }
+
+    #[inline]
+    pub fn chebyshev_len(&self) -> i32 {
+        self.0.abs().reduce_max()
+    }
+
+    #[inline]
+    pub fn euclidean_len_squared(&self) -> i64 {
+        self.dot(self)
+    }
+
+    #[inline]
+    pub fn dot(&self, other: &Vector) -> i64 {
+        (self.0.cast::<i64>() * other.0.cast::<i64>()).reduce_sum()
+    }
+
+    /// The z component of the cross product, positive when `other` is clockwise from `self`
+    /// (with y pointing down).
+    #[inline]
+    pub fn cross(&self, other: &Vector) -> i64 {
+        let [a, b]: [i64; 2] =
+            (self.0.cast::<i64>() * simd_swizzle!(other.0, [1, 0]).cast::<i64>()).to_array();
+        a - b
+    }
+
+    /// The shortest vector with integer components pointing in the same direction.
+    #[inline]
+    pub fn primitive(&self) -> Self {
+        let [x, y]: [i32; 2] = self.0.abs().to_array();
+        match gcd(x, y) {
+            0 => *self,
+            divisor => *self / divisor,
+        }
+    }
+}
+
+const fn gcd(mut a: i32, mut b: i32) -> i32 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
 }
 
 impl const From<[i32; 2]> for Vector {
@@ -291,6 +337,45 @@ fn test_manhattan_len() {
     assert_eq!(2, Vector::new(-1, -1).manhattan_len());
 }
 
+#[test]
+fn test_chebyshev_len() {
+    assert_eq!(0, Vector::new(0, 0).chebyshev_len());
+    assert_eq!(1, Vector::new(-1, 1).chebyshev_len());
+    assert_eq!(3, Vector::new(2, -3).chebyshev_len());
+    assert_eq!(4, Point::new(-1, 2).chebyshev_distance(&Point::new(3, 0)));
+}
+
+#[test]
+fn test_euclidean_len_squared() {
+    assert_eq!(25, Vector::new(3, -4).euclidean_len_squared());
+    assert_eq!(
+        2 * 4_000_000i64 * 4_000_000,
+        Vector::new(4_000_000, 4_000_000).euclidean_len_squared()
+    );
+}
+
+#[test]
+fn test_dot_and_cross() {
+    assert_eq!(0, RIGHT.dot(&DOWN));
+    assert_eq!(-1, RIGHT.dot(&LEFT));
+    assert_eq!(11, Vector::new(1, 2).dot(&Vector::new(3, 4)));
+
+    assert_eq!(1, RIGHT.cross(&DOWN));
+    assert_eq!(-1, DOWN.cross(&RIGHT));
+    assert_eq!(0, RIGHT.cross(&(LEFT * 3)));
+    assert_eq!(-2, Vector::new(1, 2).cross(&Vector::new(3, 4)));
+}
+
+#[test]
+fn test_primitive() {
+    assert_eq!(Vector::new(2, -3), Vector::new(4, -6).primitive());
+    assert_eq!(DOWN, Vector::new(0, 7).primitive());
+    assert_eq!(LEFT, Vector::new(-5, 0).primitive());
+    assert_eq!(Vector::new(-1, -1), Vector::new(-3, -3).primitive());
+    assert_eq!(Vector::new(3, 5), Vector::new(3, 5).primitive());
+    assert_eq!(Vector::new(0, 0), Vector::new(0, 0).primitive());
+}
+
 #[derive(Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct Bounds(Point, Point);
 
@@ -357,6 +442,122 @@ impl Bounds {
         }
     }
 
+    #[inline]
+    pub fn expand(&self, amount: i32) -> Bounds {
+        if self.is_empty() {
+            *self
+        } else {
+            let amount = Simd::splat(amount);
+            Bounds(Point(self.0 .0 - amount), Point(self.1 .0 + amount)).or_empty()
+        }
+    }
+
+    #[inline]
+    pub fn intersection(&self, b: &Bounds) -> Bounds {
+        let top_left = self.top_left().0.simd_max(b.top_left().0);
+        let bottom_right = self.bottom_right().0.simd_min(b.bottom_right().0);
+        Bounds(Point(top_left), Point(bottom_right)).or_empty()
+    }
+
+    #[inline]
+    pub fn overlaps(&self, b: &Bounds) -> bool {
+        !self.intersection(b).is_empty()
+    }
+
+    #[inline]
+    pub fn contains_bounds(&self, b: &Bounds) -> bool {
+        b.is_empty() || (self.contains(&b.top_left()) && self.contains(&b.bottom_right()))
+    }
+
+    #[inline]
+    fn or_empty(self) -> Bounds {
+        if self.is_empty() {
+            Bounds::EMPTY
+        } else {
+            self
+        }
+    }
+
+    /// The parts of `self` not covered by `b`, as up to four disjoint bounds: the full-width
+    /// bands above and below `b`, and the parts left and right of `b` between those bands.
+    pub fn subtract(&self, b: &Bounds) -> impl Iterator<Item = Bounds> {
+        let cut = self.intersection(b);
+        let parts = if cut.is_empty() {
+            [*self, Bounds::EMPTY, Bounds::EMPTY, Bounds::EMPTY]
+        } else {
+            [
+                Bounds(self.top_left(), Point::new(self.right(), cut.top() - 1)),
+                Bounds(
+                    Point::new(self.left(), cut.bottom() + 1),
+                    self.bottom_right(),
+                ),
+                Bounds(
+                    Point::new(self.left(), cut.top()),
+                    Point::new(cut.left() - 1, cut.bottom()),
+                ),
+                Bounds(
+                    Point::new(cut.right() + 1, cut.top()),
+                    Point::new(self.right(), cut.bottom()),
+                ),
+            ]
+        };
+        parts.into_iter().filter(|part| !part.is_empty())
+    }
+
+    /// Splits into top left, top right, bottom left and bottom right quadrants. When a side has
+    /// odd length the extra row or column goes to the top and left quadrants, and quadrants that
+    /// would have no area are [`Bounds::EMPTY`].
+    pub fn quadrants(&self) -> [Bounds; 4] {
+        if self.is_empty() {
+            return [Bounds::EMPTY; 4];
+        }
+        let half = (self.size().0 + Self::SIZE_ADJUST) / Simd::splat(2);
+        let center = Point(self.top_left().0 + half - Self::SIZE_ADJUST);
+        [
+            Bounds(self.top_left(), center),
+            Bounds(
+                Point::new(center.x() + 1, self.top()),
+                Point::new(self.right(), center.y()),
+            ),
+            Bounds(
+                Point::new(self.left(), center.y() + 1),
+                Point::new(center.x(), self.bottom()),
+            ),
+            Bounds(center + [1, 1], self.bottom_right()),
+        ]
+        .map(Bounds::or_empty)
+    }
+
+    #[inline]
+    pub fn area(&self) -> i64 {
+        if self.is_empty() {
+            0
+        } else {
+            let [width, height]: [i32; 2] = self.size().into();
+            width as i64 * height as i64
+        }
+    }
+
+    /// Splits a set of possibly overlapping bounds into disjoint bounds covering the same points.
+    pub fn disjoint_union<'a>(bounds: impl IntoIterator<Item = &'a Bounds>) -> Vec<Bounds> {
+        let mut disjoint = Vec::<Bounds>::new();
+        for b in bounds {
+            let mut parts = vec![*b];
+            for existing in &disjoint {
+                parts = parts
+                    .iter()
+                    .flat_map(|part| part.subtract(existing))
+                    .collect();
+            }
+            disjoint.extend(parts);
+        }
+        disjoint
+    }
+
+    pub fn union_area<'a>(bounds: impl IntoIterator<Item = &'a Bounds>) -> i64 {
+        Self::disjoint_union(bounds).iter().map(Bounds::area).sum()
+    }
+
     #[inline]
     pub const fn top_left(&self) -> Point {
         self.0
@@ -787,3 +988,109 @@ fn test_accessors() {
     assert_eq!(Point::new(4, 3), bounds.bottom_right());
     assert_eq!(Point::new(2, 3), bounds.bottom_left());
 }
+
+#[test]
+fn test_bounds_expand() {
+    let bounds = Bounds::new(-1, 2, 3, 0);
+    assert_eq!(Bounds::new(-3, 4, 5, -2), bounds.expand(2));
+    assert_eq!(Bounds::new(0, 1, 2, 1), bounds.expand(-1));
+    assert_eq!(Bounds::EMPTY, bounds.expand(-2));
+    assert_eq!(Bounds::EMPTY, Bounds::EMPTY.expand(1));
+}
+
+#[test]
+fn test_bounds_intersection() {
+    let a = Bounds::new(0, 4, 4, 0);
+    let b = Bounds::new(2, 6, 6, 3);
+    assert_eq!(Bounds::new(2, 4, 4, 3), a.intersection(&b));
+    assert_eq!(a.intersection(&b), b.intersection(&a));
+    assert!(a.overlaps(&b));
+
+    let c = Bounds::new(5, 4, 6, 0);
+    assert_eq!(Bounds::EMPTY, a.intersection(&c));
+    assert!(!a.overlaps(&c));
+    assert!(!a.overlaps(&Bounds::EMPTY));
+}
+
+#[test]
+fn test_bounds_contains_bounds() {
+    let a = Bounds::new(-2, 2, 2, -2);
+    assert!(a.contains_bounds(&a));
+    assert!(a.contains_bounds(&Bounds::new(-1, 2, 0, 0)));
+    assert!(!a.contains_bounds(&Bounds::new(-1, 3, 0, 0)));
+    assert!(a.contains_bounds(&Bounds::EMPTY));
+    assert!(!Bounds::EMPTY.contains_bounds(&a));
+}
+
+#[test]
+fn test_bounds_subtract() {
+    let a = Bounds::new(0, 4, 4, 0);
+
+    let hole = a.subtract(&Bounds::new(1, 2, 2, 1)).collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            Bounds::new(0, 4, 0, 0),
+            Bounds::new(3, 4, 4, 0),
+            Bounds::new(1, 0, 2, 0),
+            Bounds::new(1, 4, 2, 3),
+        ],
+        hole
+    );
+    assert_eq!(a.area() - 4, hole.iter().map(Bounds::area).sum::<i64>());
+
+    let corner = a.subtract(&Bounds::new(-5, 1, 1, -5)).collect::<Vec<_>>();
+    assert_eq!(
+        vec![Bounds::new(2, 4, 4, 0), Bounds::new(0, 4, 1, 2)],
+        corner
+    );
+
+    assert_eq!(0, a.subtract(&Bounds::new(-1, 5, 5, -1)).count());
+    assert_eq!(
+        vec![a],
+        a.subtract(&Bounds::new(5, 5, 5, 5)).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_bounds_quadrants() {
+    assert_eq!(
+        [
+            Bounds::new(-2, 0, 0, -2),
+            Bounds::new(-2, 2, 0, 1),
+            Bounds::new(1, 0, 2, -2),
+            Bounds::new(1, 2, 2, 1),
+        ],
+        Bounds::new(-2, 2, 2, -2).quadrants()
+    );
+    assert_eq!(
+        [
+            Bounds::new(0, 1, 0, 0),
+            Bounds::new(0, 2, 0, 2),
+            Bounds::EMPTY,
+            Bounds::EMPTY,
+        ],
+        Bounds::new(0, 2, 0, 0).quadrants()
+    );
+    assert_eq!([Bounds::EMPTY; 4], Bounds::EMPTY.quadrants());
+}
+
+#[test]
+fn test_bounds_union_area() {
+    let bounds = [
+        Bounds::new(0, 3, 3, 0),
+        Bounds::new(2, 5, 5, 2),
+        Bounds::new(1, 2, 2, 1),
+        Bounds::new(10, 10, 10, 10),
+    ];
+    assert_eq!(16 + 16 - 4 + 1, Bounds::union_area(&bounds));
+
+    let disjoint = Bounds::disjoint_union(&bounds);
+    for (i, a) in disjoint.iter().enumerate() {
+        for b in &disjoint[i + 1..] {
+            assert!(!a.overlaps(b));
+        }
+    }
+
+    let huge = Bounds::new(-4_000_000, 4_000_000, 4_000_000, -4_000_000);
+    assert_eq!(8_000_001 * 8_000_001, Bounds::union_area(&[huge, huge]));
+}
@@ -0,0 +1,343 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+/// An instruction of a toy instruction set, run by a [`Machine`].
+pub trait Instruction: Sized {
+    fn parse(line: &str) -> Option<Self>;
+
+    /// The number of cycles the instruction takes. It is executed in the last one.
+    #[inline]
+    fn cycles(&self) -> usize {
+        1
+    }
+
+    fn execute(&self, state: &mut State) -> Flow;
+}
+
+/// Where execution continues after an instruction.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Flow {
+    Next,
+    /// Jumps relative to the instruction.
+    Jump(isize),
+    /// Jumps to an absolute instruction index.
+    Goto(usize),
+    Halt,
+}
+
+/// The registers and memory instructions work on.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct State {
+    pub registers: Vec<i64>,
+    pub memory: Vec<i64>,
+}
+
+/// The index of the register named by a single lowercase letter, for instruction sets that
+/// name their registers `a`, `b` and so on.
+pub fn register(name: &str) -> Option<usize> {
+    match name.as_bytes() {
+        [c @ b'a'..=b'z'] => Some((c - b'a') as usize),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub text: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid instruction on line {}: {:?}",
+            self.line, self.text
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses one instruction per line, skipping blank lines.
+pub fn parse_program<I: Instruction>(source: &str) -> Result<Vec<I>, ParseError> {
+    source
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            I::parse(line.trim()).ok_or_else(|| ParseError {
+                line: i + 1,
+                text: line.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// How [`Machine::run`] decides that a program will never halt.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LoopDetection {
+    /// Never, so a looping program runs forever.
+    Off,
+    /// When an instruction is about to run a second time. Only right for programs without
+    /// conditional jumps.
+    Instruction,
+    /// When registers, memory and instruction pointer repeat.
+    State,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Halted,
+    /// The instruction about to run when the loop was detected.
+    Looped {
+        pc: usize,
+    },
+}
+
+/// What happened in one cycle.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Tick {
+    pub cycle: usize,
+    pub pc: usize,
+    /// Whether the instruction was executed in this cycle, rather than still being busy.
+    pub completed: bool,
+}
+
+pub struct Machine<I> {
+    program: Vec<I>,
+    state: State,
+    pc: usize,
+    busy: usize,
+    cycle: usize,
+}
+
+impl<I: Instruction> Machine<I> {
+    /// A machine with `registers` zeroed registers and no memory.
+    pub fn new(program: Vec<I>, registers: usize) -> Self {
+        Self {
+            program,
+            state: State {
+                registers: vec![0; registers],
+                memory: vec![],
+            },
+            pc: 0,
+            busy: 0,
+            cycle: 0,
+        }
+    }
+
+    pub fn with_memory(mut self, words: usize) -> Self {
+        self.state.memory = vec![0; words];
+        self
+    }
+
+    pub fn with_register(mut self, register: usize, value: i64) -> Self {
+        self.state.registers[register] = value;
+        self
+    }
+
+    #[inline]
+    pub fn program(&self) -> &[I] {
+        &self.program
+    }
+
+    #[inline]
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    #[inline]
+    pub fn state_mut(&mut self) -> &mut State {
+        &mut self.state
+    }
+
+    #[inline]
+    pub fn registers(&self) -> &[i64] {
+        &self.state.registers
+    }
+
+    /// The index of the next instruction.
+    #[inline]
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// The number of cycles run so far, or while observing a cycle, the number of that cycle.
+    #[inline]
+    pub fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    /// The cycles already spent on the instruction at [`Machine::pc`].
+    #[inline]
+    pub fn busy(&self) -> usize {
+        self.busy
+    }
+
+    #[inline]
+    pub fn current(&self) -> Option<&I> {
+        self.program.get(self.pc)
+    }
+
+    #[inline]
+    pub fn is_halted(&self) -> bool {
+        self.pc >= self.program.len()
+    }
+
+    pub fn tick(&mut self) -> Option<Tick> {
+        self.tick_observed(|_| {})
+    }
+
+    /// Runs one cycle, calling `observer` during the cycle, before an instruction executed in
+    /// it takes effect. Returns `None` once the program has halted.
+    pub fn tick_observed(&mut self, mut observer: impl FnMut(&Self)) -> Option<Tick> {
+        let instruction = self.program.get(self.pc)?;
+        let cycles = instruction.cycles();
+        self.cycle += 1;
+        self.busy += 1;
+        observer(self);
+
+        let pc = self.pc;
+        let completed = self.busy >= cycles;
+        if completed {
+            let flow = self.program[pc].execute(&mut self.state);
+            self.busy = 0;
+            self.pc = match flow {
+                Flow::Next => pc + 1,
+                Flow::Jump(offset) => (pc as isize + offset).try_into().unwrap_or(usize::MAX),
+                Flow::Goto(target) => target,
+                Flow::Halt => usize::MAX,
+            };
+        }
+        Some(Tick {
+            cycle: self.cycle,
+            pc,
+            completed,
+        })
+    }
+
+    pub fn run(&mut self, detection: LoopDetection) -> Outcome {
+        self.run_observed(detection, |_| {})
+    }
+
+    /// Runs until the program halts or loops, calling `observer` during every cycle.
+    pub fn run_observed(
+        &mut self,
+        detection: LoopDetection,
+        mut observer: impl FnMut(&Self),
+    ) -> Outcome {
+        let mut seen_pcs = HashSet::new();
+        let mut seen_states = HashSet::new();
+        while !self.is_halted() {
+            if self.busy == 0 {
+                let repeated = match detection {
+                    LoopDetection::Off => false,
+                    LoopDetection::Instruction => !seen_pcs.insert(self.pc),
+                    LoopDetection::State => !seen_states.insert((self.pc, self.state.clone())),
+                };
+                if repeated {
+                    return Outcome::Looped { pc: self.pc };
+                }
+            }
+            self.tick_observed(&mut observer);
+        }
+        Outcome::Halted
+    }
+}
+
+#[cfg(test)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Op {
+    Inc(usize),
+    Dec(usize),
+    Store(usize, usize),
+    Jnz(usize, isize),
+    Nop(isize),
+}
+
+#[cfg(test)]
+impl Instruction for Op {
+    fn parse(line: &str) -> Option<Self> {
+        let words = line.split_ascii_whitespace().collect::<Vec<_>>();
+        match words.as_slice() {
+            ["inc", r] => Some(Op::Inc(register(r)?)),
+            ["dec", r] => Some(Op::Dec(register(r)?)),
+            ["store", r, address] => Some(Op::Store(register(r)?, address.parse().ok()?)),
+            ["jnz", r, offset] => Some(Op::Jnz(register(r)?, offset.parse().ok()?)),
+            ["nop", offset] => Some(Op::Nop(offset.parse().ok()?)),
+            _ => None,
+        }
+    }
+
+    fn cycles(&self) -> usize {
+        match self {
+            Op::Store(..) => 3,
+            _ => 1,
+        }
+    }
+
+    fn execute(&self, state: &mut State) -> Flow {
+        match *self {
+            Op::Inc(r) => state.registers[r] += 1,
+            Op::Dec(r) => state.registers[r] -= 1,
+            Op::Store(r, address) => state.memory[address] = state.registers[r],
+            Op::Jnz(r, offset) if state.registers[r] != 0 => return Flow::Jump(offset),
+            Op::Jnz(..) | Op::Nop(_) => {}
+        }
+        Flow::Next
+    }
+}
+
+#[test]
+fn test_machine_countdown() {
+    let a = register("a").unwrap();
+    let program = parse_program::<Op>("inc b\ninc b\ndec a\njnz a -3\n\nstore b 1\n").unwrap();
+    let mut machine = Machine::new(program, 2).with_memory(2).with_register(a, 3);
+
+    let mut observed = vec![];
+    let outcome = machine.run_observed(LoopDetection::State, |m| {
+        observed.push((m.cycle(), m.pc()));
+    });
+    assert_eq!(Outcome::Halted, outcome);
+    assert_eq!(12 + 3, machine.cycle());
+    assert_eq!(vec![0, 6], machine.state().memory);
+    assert_eq!((1, 0), observed[0]);
+    assert_eq!(&[(13, 4), (14, 4), (15, 4)], &observed[12..]);
+}
+
+#[test]
+fn test_machine_loop_detection() {
+    let program = parse_program::<Op>("inc a\nnop 1\njnz a -2").unwrap();
+    let outcome = Machine::new(program, 1).run(LoopDetection::Instruction);
+    assert_eq!(Outcome::Looped { pc: 0 }, outcome);
+
+    // Counts `a` down from 2, so the jump back is legitimate the first time around.
+    let countdown = "dec a\njnz a -1\ninc b";
+    let program = parse_program::<Op>(countdown).unwrap();
+    let mut machine = Machine::new(program, 2).with_register(0, 2);
+    assert_eq!(
+        Outcome::Looped { pc: 0 },
+        machine.run(LoopDetection::Instruction)
+    );
+    let program = parse_program::<Op>(countdown).unwrap();
+    let mut machine = Machine::new(program, 2).with_register(0, 2);
+    assert_eq!(Outcome::Halted, machine.run(LoopDetection::State));
+    assert_eq!(&[0, 1], machine.registers());
+
+    let program = parse_program::<Op>("inc a\njnz a 0").unwrap();
+    let mut machine = Machine::new(program, 1);
+    assert_eq!(Outcome::Looped { pc: 1 }, machine.run(LoopDetection::State));
+    assert_eq!(Some(&Op::Jnz(0, 0)), machine.current());
+}
+
+#[test]
+fn test_parse_error() {
+    assert_eq!(
+        Err(ParseError {
+            line: 2,
+            text: "mul a".to_string()
+        }),
+        parse_program::<Op>("inc a\nmul a\n")
+    );
+}
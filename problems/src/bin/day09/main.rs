@@ -31,7 +31,7 @@ fn parse_moves(input: &str) -> impl Iterator<Item = Vector> + '_ {
 
 fn move_tail(head: &Point, tail: &Point) -> Point {
     let diff = head.vector(tail);
-    if diff.abs().max_component() <= 1 {
+    if diff.chebyshev_len() <= 1 {
         *tail
     } else {
         *tail + diff.signum()
@@ -1,65 +1,383 @@
+use adventofcode2022_common::ansi::LiveView;
 use adventofcode2022_common::charcanvas::CharCanvas;
+use adventofcode2022_common::ocr;
+use adventofcode2022_common::vm::{self, Flow, LoopDetection, Machine, State, Tick};
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, Write};
+use std::time::Duration;
 
 const INPUT: &str = include_str!("input.txt");
 #[cfg(test)]
 const EXAMPLE: &str = include_str!("example.txt");
 
 fn main() {
+    if std::env::args().any(|arg| arg == "--debug") {
+        let mut cpu = Cpu::new(parse_program(INPUT)).traced();
+        let stdin = std::io::stdin();
+        debug(&mut cpu, stdin.lock(), &mut std::io::stdout()).unwrap();
+        return;
+    }
     eprintln!("part1 {:?}", part1(INPUT));
-    eprintln!("part2 \n{}", part2(INPUT));
+    let mut live = LiveView::from_args().delay(Duration::from_millis(10));
+    eprintln!("part2 {:?}", part2_with(INPUT, &mut live));
+}
+
+/// The registers of the CPU, by index.
+const REGISTER_NAMES: [&str; 1] = ["x"];
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Register(usize);
+
+impl Register {
+    const X: Register = Register(0);
+
+    fn parse(name: &str) -> Option<Register> {
+        REGISTER_NAMES.iter().position(|n| *n == name).map(Register)
+    }
+}
+
+impl Display for Register {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", REGISTER_NAMES[self.0])
+    }
+}
+
+/// Shows every register, like `x=4`.
+fn format_registers(registers: &[i64]) -> String {
+    let shown = (0..registers.len())
+        .map(|i| format!("{}={}", Register(i), registers[i]))
+        .collect::<Vec<_>>();
+    shown.join(" ")
 }
 
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 enum Instruction {
     Noop,
-    Add(i32),
+    Add(Register, i64),
+}
+
+impl vm::Instruction for Instruction {
+    fn parse(line: &str) -> Option<Instruction> {
+        match line.split_ascii_whitespace().collect::<Vec<_>>().as_slice() {
+            ["noop"] => Some(Instruction::Noop),
+            [add, v] => Some(Instruction::Add(
+                Register::parse(add.strip_prefix("add")?)?,
+                v.parse().ok()?,
+            )),
+            _ => None,
+        }
+    }
+
+    fn cycles(&self) -> usize {
+        match self {
+            Instruction::Noop => 1,
+            Instruction::Add(..) => 2,
+        }
+    }
+
+    fn execute(&self, state: &mut State) -> Flow {
+        if let Instruction::Add(register, v) = self {
+            state.registers[register.0] += v;
+        }
+        Flow::Next
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instruction::Noop => write!(f, "noop"),
+            Instruction::Add(register, v) => write!(f, "add{} {}", register, v),
+        }
+    }
+}
+
+/// An instruction as it completed, with the registers after it took effect.
+#[derive(Clone, Debug)]
+struct TraceEntry {
+    cycle: usize,
+    pc: usize,
+    instruction: Instruction,
+    registers: [i64; REGISTER_NAMES.len()],
+}
+
+impl Display for TraceEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:>5} {:>4}  {:<10} {}",
+            self.cycle,
+            self.pc,
+            self.instruction.to_string(),
+            format_registers(&self.registers)
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    const SYMBOLS: [(&'static str, Comparison); 6] = [
+        ("==", Comparison::Eq),
+        ("!=", Comparison::Ne),
+        ("<=", Comparison::Le),
+        (">=", Comparison::Ge),
+        ("<", Comparison::Lt),
+        (">", Comparison::Gt),
+    ];
+
+    fn symbol(&self) -> &'static str {
+        Self::SYMBOLS.iter().find(|(_, c)| c == self).unwrap().0
+    }
+
+    fn compare(&self, a: i64, b: i64) -> bool {
+        match self {
+            Comparison::Eq => a == b,
+            Comparison::Ne => a != b,
+            Comparison::Lt => a < b,
+            Comparison::Le => a <= b,
+            Comparison::Gt => a > b,
+            Comparison::Ge => a >= b,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Breakpoint {
+    Cycle(usize),
+    Register(Register, Comparison, i64),
+}
+
+impl Breakpoint {
+    /// Parses a cycle number like `20` or a register condition like `x >= 5`.
+    fn parse(s: &str) -> Option<Breakpoint> {
+        let s = s.trim();
+        if let Ok(cycle) = s.parse() {
+            return Some(Breakpoint::Cycle(cycle));
+        }
+        let (symbol, comparison) = Comparison::SYMBOLS
+            .into_iter()
+            .find(|(symbol, _)| s.contains(symbol))?;
+        let (register, value) = s.split_once(symbol)?;
+        Some(Breakpoint::Register(
+            Register::parse(register.trim())?,
+            comparison,
+            value.trim().parse().ok()?,
+        ))
+    }
+
+    /// Whether to stop before `cycle`. Register conditions only stop when they become true,
+    /// so continuing from one does not stop again right away.
+    fn is_hit(&self, cycle: usize, before: &[i64], after: &[i64]) -> bool {
+        match self {
+            Breakpoint::Cycle(at) => cycle == *at,
+            Breakpoint::Register(register, comparison, value) => {
+                let holds = |registers: &[i64]| comparison.compare(registers[register.0], *value);
+                !holds(before) && holds(after)
+            }
+        }
+    }
+}
+
+impl Display for Breakpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Breakpoint::Cycle(cycle) => write!(f, "cycle {}", cycle),
+            Breakpoint::Register(register, comparison, value) => {
+                write!(f, "{} {} {}", register, comparison.symbol(), value)
+            }
+        }
+    }
 }
 
+/// A [`Machine`] running the CRT program, with the tracing and breakpoints of the debugger.
 struct Cpu {
-    x: i32,
+    machine: Machine<Instruction>,
+    trace: Option<Vec<TraceEntry>>,
+    breakpoints: Vec<Breakpoint>,
 }
 
 impl Cpu {
-    fn execute(&mut self, instruction: Instruction) -> i32 {
-        let x = self.x;
-        if let Instruction::Add(dx) = instruction {
-            self.x += dx;
+    fn new(program: Vec<Instruction>) -> Self {
+        Self {
+            machine: Machine::new(program, REGISTER_NAMES.len()).with_register(Register::X.0, 1),
+            trace: None,
+            breakpoints: vec![],
         }
-        x
     }
-}
 
-impl Default for Cpu {
-    fn default() -> Self {
-        Self { x: 1 }
+    fn traced(mut self) -> Self {
+        self.trace = Some(vec![]);
+        self
+    }
+
+    #[inline]
+    fn registers(&self) -> &[i64] {
+        self.machine.registers()
+    }
+
+    /// A copy of the registers that doesn't need an allocation.
+    #[inline]
+    fn register_snapshot(&self) -> [i64; REGISTER_NAMES.len()] {
+        self.registers().try_into().unwrap()
+    }
+
+    fn tick(&mut self) -> Option<Tick> {
+        let tick = self.machine.tick()?;
+        let registers = self.register_snapshot();
+        if let (Some(trace), true) = (&mut self.trace, tick.completed) {
+            trace.push(TraceEntry {
+                cycle: tick.cycle,
+                pc: tick.pc,
+                instruction: self.machine.program()[tick.pc],
+                registers,
+            });
+        }
+        Some(tick)
+    }
+
+    /// Runs at least one cycle, and then until a breakpoint is hit. Returns the index of that
+    /// breakpoint, or `None` if the program finished without hitting one.
+    fn run(&mut self) -> Option<usize> {
+        loop {
+            let before = self.register_snapshot();
+            self.tick()?;
+            let cycle = self.machine.cycle() + 1;
+            let hit = self
+                .breakpoints
+                .iter()
+                .position(|b| b.is_hit(cycle, &before, self.registers()));
+            if hit.is_some() || self.machine.is_halted() {
+                return hit;
+            }
+        }
+    }
+
+    fn status(&self) -> String {
+        let registers = format_registers(self.registers());
+        match self.machine.current() {
+            Some(instruction) => format!(
+                "cycle {}, pc {}: {} ({}/{}), {}",
+                self.machine.cycle() + 1,
+                self.machine.pc(),
+                instruction,
+                self.machine.busy() + 1,
+                vm::Instruction::cycles(instruction),
+                registers
+            ),
+            None => format!(
+                "halted after {} cycles, {}",
+                self.machine.cycle(),
+                registers
+            ),
+        }
     }
 }
 
-fn parse_instructions(line: &str) -> Vec<Instruction> {
-    match line.split_ascii_whitespace().collect::<Vec<_>>().as_slice() {
-        ["noop"] => vec![Instruction::Noop],
-        ["addx", i] => vec![Instruction::Noop, Instruction::Add(i.parse().unwrap())],
-        _ => unreachable!(),
+const DEBUGGER_HELP: &str = "\
+step [n]        run n cycles, 1 by default
+continue        run until a breakpoint is hit or the program ends
+break <cond>    stop before a cycle, e.g. `break 20`, or on a register, e.g. `break x > 5`
+delete <n>      remove breakpoint n
+breakpoints     list breakpoints
+registers       show the registers
+trace [n]       show the last n completed instructions, 10 by default
+quit";
+
+/// A step/continue debugger reading commands from `input`. Commands can be abbreviated to
+/// their first letter.
+fn debug(cpu: &mut Cpu, input: impl BufRead, out: &mut impl Write) -> std::io::Result<()> {
+    writeln!(out, "{}", cpu.status())?;
+    for line in input.lines() {
+        let line = line?;
+        let (command, argument) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+        let count = |default| argument.trim().parse().unwrap_or(default);
+        match command {
+            "" => continue,
+            "s" | "step" => {
+                for _ in 0..count(1) {
+                    cpu.tick();
+                }
+            }
+            "c" | "continue" => {
+                if let Some(i) = cpu.run() {
+                    writeln!(out, "breakpoint {}: {}", i, cpu.breakpoints[i])?;
+                }
+            }
+            "b" | "break" => match Breakpoint::parse(argument) {
+                Some(breakpoint) => {
+                    cpu.breakpoints.push(breakpoint);
+                    writeln!(
+                        out,
+                        "breakpoint {}: {}",
+                        cpu.breakpoints.len() - 1,
+                        breakpoint
+                    )?;
+                }
+                None => writeln!(out, "invalid breakpoint {:?}", argument)?,
+            },
+            "d" | "delete" => {
+                let i = count(usize::MAX);
+                if i < cpu.breakpoints.len() {
+                    cpu.breakpoints.remove(i);
+                } else {
+                    writeln!(out, "no breakpoint {:?}", argument)?;
+                }
+            }
+            "breakpoints" => {
+                for (i, breakpoint) in cpu.breakpoints.iter().enumerate() {
+                    writeln!(out, "{}: {}", i, breakpoint)?;
+                }
+            }
+            "r" | "registers" => writeln!(out, "{}", format_registers(cpu.registers()))?,
+            "t" | "trace" => match &cpu.trace {
+                Some(trace) => {
+                    for entry in &trace[trace.len().saturating_sub(count(10))..] {
+                        writeln!(out, "{}", entry)?;
+                    }
+                }
+                None => writeln!(out, "tracing is off")?,
+            },
+            "q" | "quit" => return Ok(()),
+            _ => {
+                writeln!(out, "{}", DEBUGGER_HELP)?;
+                continue;
+            }
+        }
+        writeln!(out, "{}", cpu.status())?;
     }
+    Ok(())
+}
+
+fn parse_program(input: &str) -> Vec<Instruction> {
+    vm::parse_program(input).unwrap()
 }
 
-fn generate_signal(input: &str) -> impl Iterator<Item = i32> + '_ {
-    input
-        .lines()
-        .flat_map(parse_instructions)
-        .scan(Cpu::default(), |cpu, instruction| {
-            Some(cpu.execute(instruction))
-        })
+/// Runs the program, calling `observer` with the cycle number and the `x` register during
+/// every cycle.
+fn run_crt(input: &str, mut observer: impl FnMut(usize, i64)) {
+    let mut cpu = Cpu::new(parse_program(input));
+    cpu.machine.run_observed(LoopDetection::Off, |machine| {
+        observer(machine.cycle(), machine.registers()[Register::X.0])
+    });
 }
 
-fn part1(input: &str) -> i32 {
-    generate_signal(input)
-        .enumerate()
-        .skip(19)
-        .step_by(40)
-        .fold(0, |signal_strength, (cycle, reg)| {
-            signal_strength + ((cycle + 1) as i32 * reg)
-        })
+fn part1(input: &str) -> i64 {
+    let mut signal_strength = 0;
+    run_crt(input, |cycle, x| {
+        if cycle % 40 == 20 {
+            signal_strength += cycle as i64 * x;
+        }
+    });
+    signal_strength
 }
 
 #[test]
@@ -73,28 +391,133 @@ fn part1_verify() {
     assert_eq!(14620, part1(INPUT))
 }
 
+#[cfg(test)]
+fn render_crt(input: &str) -> CharCanvas {
+    render_crt_with(input, &mut LiveView::default())
+}
+
+fn render_crt_with(input: &str, live: &mut LiveView) -> CharCanvas {
+    let mut crt = CharCanvas::with_size('.', [40, 6]);
+    let mut ray_positions = crt.bounds.iter_points();
+    run_crt(input, |_, sprite_position| {
+        if let Some(ray_position) = ray_positions.next() {
+            let ray_x = ray_position.x() as i64;
+            crt[ray_position] = if (sprite_position - 1..=sprite_position + 1).contains(&ray_x) {
+                '#'
+            } else {
+                '.'
+            };
+            live.draw(&crt, |tile| (tile == '#').then_some([255, 255, 255]));
+        }
+    });
+    crt
+}
+
+#[cfg(test)]
 fn part2(input: &str) -> String {
-    let mut map = CharCanvas::with_size('.', [40, 6]);
-    generate_signal(input)
-        .zip(map.bounds.iter_points())
-        .for_each(|(sprite_position, ray_position)| {
-            map[ray_position] =
-                if (sprite_position - 1..=sprite_position + 1).contains(&ray_position.x()) {
-                    '#'
-                } else {
-                    '.'
-                };
-        });
-    map.to_string()
+    part2_with(input, &mut LiveView::default())
+}
+
+fn part2_with(input: &str, live: &mut LiveView) -> String {
+    let crt = render_crt_with(input, live);
+    ocr::read_canvas(&crt).unwrap_or_else(|error| panic!("{}\n{}", error, crt))
 }
 
 #[test]
 fn part2_example() {
-    assert_eq!(include_str!("example_result.txt"), part2(EXAMPLE))
+    assert_eq!(
+        include_str!("example_result.txt"),
+        render_crt(EXAMPLE).to_string()
+    )
 }
 
 #[ignore]
 #[test]
 fn part2_verify() {
-    assert_eq!(include_str!("input_result.txt"), part2(INPUT))
+    assert_eq!(
+        include_str!("input_result.txt"),
+        render_crt(INPUT).to_string()
+    );
+    assert_eq!("BJFRHRFU", part2(INPUT))
+}
+
+#[test]
+fn cpu_cycles() {
+    assert_eq!(None, <Instruction as vm::Instruction>::parse("addy -5"));
+    let program = parse_program("noop\naddx 3\naddx -5\nnoop");
+    let mut cpu = Cpu::new(program).traced();
+    let mut xs = vec![];
+    while !cpu.machine.is_halted() {
+        xs.push(cpu.registers()[Register::X.0]);
+        cpu.tick();
+    }
+    assert_eq!(vec![1, 1, 1, 4, 4, -1], xs);
+    let trace = cpu.trace.unwrap();
+    assert_eq!(4, trace.len());
+    assert_eq!("    3    1  addx 3     x=4", trace[1].to_string());
+    assert_eq!("    5    2  addx -5    x=-1", trace[2].to_string());
+}
+
+#[test]
+fn cpu_breakpoints() {
+    assert_eq!(Some(Breakpoint::Cycle(20)), Breakpoint::parse("20"));
+    assert_eq!(
+        Some(Breakpoint::Register(Register::X, Comparison::Ge, -3)),
+        Breakpoint::parse("x >= -3")
+    );
+    assert_eq!(None, Breakpoint::parse("x ~ 3"));
+
+    let mut cpu = Cpu::new(parse_program(EXAMPLE));
+    cpu.breakpoints.push(Breakpoint::Cycle(20));
+    cpu.breakpoints.push(Breakpoint::Cycle(60));
+    assert_eq!(Some(0), cpu.run());
+    assert_eq!(21, cpu.registers()[Register::X.0]);
+    assert_eq!(Some(1), cpu.run());
+    assert_eq!(19, cpu.registers()[Register::X.0]);
+    assert_eq!(None, cpu.run());
+    assert!(cpu.machine.is_halted());
+    assert_eq!(240, cpu.machine.cycle());
+
+    let mut cpu = Cpu::new(parse_program(
+        "addx 5\naddx -10\nnoop\naddx 10\naddx -20\nnoop",
+    ));
+    cpu.breakpoints.push(Breakpoint::parse("x < 0").unwrap());
+    assert_eq!(Some(0), cpu.run());
+    assert_eq!(
+        (4, -4),
+        (cpu.machine.cycle(), cpu.registers()[Register::X.0])
+    );
+    assert_eq!(Some(0), cpu.run());
+    assert_eq!(
+        (9, -14),
+        (cpu.machine.cycle(), cpu.registers()[Register::X.0])
+    );
+
+    // The last instruction can still hit a breakpoint.
+    let mut cpu = Cpu::new(parse_program("noop\naddx -3"));
+    cpu.breakpoints.push(Breakpoint::parse("x < 0").unwrap());
+    assert_eq!(Some(0), cpu.run());
+    assert!(cpu.machine.is_halted());
+    assert_eq!(None, cpu.run());
+}
+
+#[test]
+fn cpu_debugger() {
+    let mut cpu = Cpu::new(parse_program("addx 2\nnoop\naddx -1")).traced();
+    let commands = "s\nb x == 3\nc\nt 1\nc\nhelp\nq\ns\n";
+    let mut out = vec![];
+    debug(&mut cpu, commands.as_bytes(), &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let expected = "\
+cycle 1, pc 0: addx 2 (1/2), x=1
+cycle 2, pc 0: addx 2 (2/2), x=1
+breakpoint 0: x == 3
+cycle 2, pc 0: addx 2 (2/2), x=1
+breakpoint 0: x == 3
+cycle 3, pc 1: noop (1/1), x=3
+    2    0  addx 2     x=3
+cycle 3, pc 1: noop (1/1), x=3
+halted after 5 cycles, x=2
+";
+    assert_eq!(format!("{}{}\n", expected, DEBUGGER_HELP), out);
 }
@@ -1,59 +1,68 @@
-use crate::Op::{AddBy, MulBy, Square};
+use adventofcode2022_common::expr::{BinOp, Expr, Value};
+use adventofcode2022_common::modular::ModularInt;
 use std::cmp::Reverse;
 use std::collections::VecDeque;
+use std::fmt::{Debug, Display, Formatter, Write};
+use std::num::Wrapping;
 
 const INPUT: &str = include_str!("input.txt");
 #[cfg(test)]
 const EXAMPLE: &str = include_str!("example.txt");
 
 fn main() {
-    eprintln!("part1 {:?}", part1(INPUT));
+    if std::env::args().any(|arg| arg == "--trace") {
+        print_trace(INPUT);
+    }
+    let arithmetic = if std::env::args().any(|arg| arg == "--checked") {
+        Arithmetic::Checked
+    } else {
+        Arithmetic::default()
+    };
+    eprintln!("part1 {:?}", part1_with(INPUT, arithmetic));
     eprintln!("part2 {:?}", part2(INPUT));
 }
 
-type WorryLevel = i64;
-
-fn words(line: &str) -> Vec<&str> {
-    line.split_ascii_whitespace().collect()
+/// A worry level the monkeys can do their arithmetic on. Plain `i64` arithmetic is checked,
+/// so a worry level outgrowing it is reported, while `Wrapping<i64>` silently wraps around.
+trait WorryLevel: Value + Debug + Display {
+    fn is_divisible_by(&self, divisor: u64) -> bool;
 }
 
-fn entry(line: &str) -> &str {
-    let (_, value) = line.split_once(": ").expect("value");
-    value
+impl WorryLevel for i64 {
+    fn is_divisible_by(&self, divisor: u64) -> bool {
+        self % divisor as i64 == 0
+    }
 }
 
-enum Op {
-    AddBy(WorryLevel),
-    MulBy(WorryLevel),
-    Square,
+impl WorryLevel for Wrapping<i64> {
+    fn is_divisible_by(&self, divisor: u64) -> bool {
+        self.0 % divisor as i64 == 0
+    }
 }
 
-impl Op {
-    fn apply(&self, item: WorryLevel) -> WorryLevel {
-        match self {
-            AddBy(i) => item + i,
-            MulBy(i) => item * i,
-            Square => item * item,
-        }
+impl WorryLevel for ModularInt {
+    fn is_divisible_by(&self, divisor: u64) -> bool {
+        ModularInt::is_divisible_by(self, divisor)
     }
 }
 
-fn parse_monkey(monkey: &str) -> Monkey {
+fn entry(line: &str) -> &str {
+    let (_, value) = line.split_once(": ").expect("value");
+    value
+}
+
+fn parse_monkey(monkey: &str) -> Monkey<i64> {
     let lines = monkey.lines().collect::<Vec<_>>();
     let items = entry(lines[1])
         .split_terminator(", ")
-        .map(|item| item.parse::<WorryLevel>().expect("item number"))
+        .map(|item| item.parse::<i64>().expect("item number"))
         .collect::<VecDeque<_>>();
 
-    let operation = words(entry(lines[2]));
-    let operation = match operation.as_slice() {
-        ["new", "=", "old", "+", value] => AddBy(value.parse().unwrap()),
-        ["new", "=", "old", "*", "old"] => Square,
-        ["new", "=", "old", "*", value] => MulBy(value.parse().unwrap()),
-        _ => unreachable!(),
-    };
+    let (_, operation) = entry(lines[2]).split_once('=').expect("new = expression");
+    // Not simplified, since that folds constants in i64 arithmetic rather than modular.
+    let operation = operation.parse::<Expr>().unwrap();
     let (_, is_divisible_by) = entry(lines[3]).rsplit_once(' ').unwrap();
-    let is_divisible_by = is_divisible_by.parse::<WorryLevel>().unwrap();
+    let is_divisible_by = is_divisible_by.parse::<u64>().unwrap();
     let (_, if_true) = lines[4].rsplit_once(' ').unwrap();
     let if_true = if_true.parse::<usize>().unwrap();
     let (_, if_false) = lines[5].rsplit_once(' ').unwrap();
@@ -67,66 +76,239 @@ fn parse_monkey(monkey: &str) -> Monkey {
     }
 }
 
-struct Monkey {
-    items: VecDeque<WorryLevel>,
-    operation: Op,
-    is_divisible_by: WorryLevel,
+struct Monkey<W> {
+    items: VecDeque<W>,
+    /// The new worry level, in terms of the `old` one.
+    operation: Expr,
+    is_divisible_by: u64,
     if_true: usize,
     if_false: usize,
 }
 
-impl Monkey {
-    fn throw<F: Fn(WorryLevel) -> WorryLevel>(
-        &mut self,
-        worry_level_modifier: F,
-    ) -> Option<(usize, WorryLevel)> {
-        if let Some(item) = self.items.pop_front() {
-            let worry_level = worry_level_modifier(self.operation.apply(item));
-            let target_monkey = if worry_level % self.is_divisible_by == 0 {
-                self.if_true
-            } else {
-                self.if_false
-            };
-            Some((target_monkey, worry_level))
-        } else {
-            None
+impl<W: WorryLevel> Monkey<W> {
+    fn inspect(&self, context: &W::Context, item: W) -> W {
+        self.operation
+            .eval_in(context, &|name| (name == "old").then_some(item))
+            .unwrap_or_else(|error| {
+                panic!(
+                    "{} in new = {} with old = {:?}",
+                    error, self.operation, item
+                )
+            })
+    }
+
+    /// How the operation changes the worry level, in the words of the puzzle.
+    fn describe_operation(&self, new: W) -> String {
+        let old = Expr::var("old");
+        match &self.operation {
+            Expr::Binary(BinOp::Mul, left, right) if **left == old && **right == old => {
+                format!("is multiplied by itself to {}", new)
+            }
+            Expr::Binary(BinOp::Mul, left, right) if **left == old => {
+                format!("is multiplied by {} to {}", right, new)
+            }
+            Expr::Binary(BinOp::Add, left, right) if **left == old => {
+                format!("increases by {} to {}", right, new)
+            }
+            operation => format!("is set to {} = {}", operation, new),
         }
     }
-    fn receive(&mut self, item: WorryLevel) {
-        self.items.push_back(item);
+
+    fn map_items<V>(self, f: impl Fn(W) -> V) -> Monkey<V> {
+        Monkey {
+            items: self.items.into_iter().map(f).collect(),
+            operation: self.operation,
+            is_divisible_by: self.is_divisible_by,
+            if_true: self.if_true,
+            if_false: self.if_false,
+        }
     }
 }
 
-fn parse_input(input: &str) -> Vec<Monkey> {
+fn parse_input(input: &str) -> Vec<Monkey<i64>> {
     input.split_terminator("\n\n").map(parse_monkey).collect()
 }
 
-fn monkey_business<const N: usize, F: Fn(WorryLevel) -> WorryLevel>(
-    mut monkeys: Vec<Monkey>,
-    worry_level_modifier: F,
-) -> i64 {
-    let mut inspections = vec![0; monkeys.len()];
-    for _ in 0..N {
-        for monkey_number in 0..monkeys.len() {
-            while let Some((target, item)) = monkeys[monkey_number].throw(&worry_level_modifier) {
-                inspections[monkey_number] += 1;
-                monkeys[target].receive(item)
+/// What happens to worry levels after an inspection that doesn't damage the item.
+struct Relief<W> {
+    description: &'static str,
+    apply: fn(W) -> W,
+}
+
+struct Simulation<W: WorryLevel> {
+    monkeys: Vec<Monkey<W>>,
+    context: W::Context,
+    relief: Option<Relief<W>>,
+    inspections: Vec<usize>,
+    rounds: usize,
+}
+
+impl<W: WorryLevel> Simulation<W> {
+    fn new(monkeys: Vec<Monkey<W>>, context: W::Context, relief: Option<Relief<W>>) -> Self {
+        let inspections = vec![0; monkeys.len()];
+        Self {
+            monkeys,
+            context,
+            relief,
+            inspections,
+            rounds: 0,
+        }
+    }
+
+    fn run(&mut self, rounds: usize) {
+        for _ in 0..rounds {
+            self.round(None);
+        }
+    }
+
+    /// Plays a round, describing every inspection in `log` the way the puzzle does.
+    fn round(&mut self, mut log: Option<&mut String>) {
+        for monkey_number in 0..self.monkeys.len() {
+            if let Some(log) = &mut log {
+                writeln!(log, "Monkey {}:", monkey_number).unwrap();
             }
+            while let Some(item) = self.monkeys[monkey_number].items.pop_front() {
+                self.inspections[monkey_number] += 1;
+                let monkey = &self.monkeys[monkey_number];
+                let new = monkey.inspect(&self.context, item);
+                let worry_level = self
+                    .relief
+                    .as_ref()
+                    .map_or(new, |relief| (relief.apply)(new));
+                let divisible = worry_level.is_divisible_by(monkey.is_divisible_by);
+                let target = if divisible {
+                    monkey.if_true
+                } else {
+                    monkey.if_false
+                };
+
+                if let Some(log) = &mut log {
+                    let log = &mut **log;
+                    writeln!(
+                        log,
+                        "  Monkey inspects an item with a worry level of {}.",
+                        item
+                    )
+                    .unwrap();
+                    writeln!(log, "    Worry level {}.", monkey.describe_operation(new)).unwrap();
+                    if let Some(relief) = &self.relief {
+                        writeln!(
+                            log,
+                            "    Monkey gets bored with item. Worry level is {} to {}.",
+                            relief.description, worry_level
+                        )
+                        .unwrap();
+                    }
+                    writeln!(
+                        log,
+                        "    Current worry level is {}divisible by {}.",
+                        if divisible { "" } else { "not " },
+                        monkey.is_divisible_by
+                    )
+                    .unwrap();
+                    writeln!(
+                        log,
+                        "    Item with worry level {} is thrown to monkey {}.",
+                        worry_level, target
+                    )
+                    .unwrap();
+                }
+                self.monkeys[target].items.push_back(worry_level);
+            }
+        }
+        self.rounds += 1;
+    }
+
+    /// The worry levels of the items each monkey holds.
+    fn holdings(&self) -> String {
+        let mut holdings = String::new();
+        for (monkey_number, monkey) in self.monkeys.iter().enumerate() {
+            let items = monkey.items.iter().map(W::to_string).collect::<Vec<_>>();
+            writeln!(holdings, "Monkey {}: {}", monkey_number, items.join(", ")).unwrap();
+        }
+        holdings
+    }
+
+    fn inspection_table(&self) -> String {
+        let mut table = format!("== After round {} ==\n", self.rounds);
+        for (monkey_number, count) in self.inspections.iter().enumerate() {
+            writeln!(
+                table,
+                "Monkey {} inspected items {} times.",
+                monkey_number, count
+            )
+            .unwrap();
         }
+        table
     }
 
-    inspections.sort_unstable_by_key(|s| Reverse(*s));
-    inspections.into_iter().take(2).product()
+    fn monkey_business(&self) -> usize {
+        let mut inspections = self.inspections.clone();
+        inspections.sort_unstable_by_key(|s| Reverse(*s));
+        inspections.into_iter().take(2).product()
+    }
+}
+
+const BORED: Relief<i64> = Relief {
+    description: "divided by 3",
+    apply: |w| w / 3,
+};
+
+const BORED_WRAPPING: Relief<Wrapping<i64>> = Relief {
+    description: BORED.description,
+    apply: |w| w / Wrapping(3),
+};
+
+/// How part 1 keeps track of worry levels.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Arithmetic {
+    /// Panics when a worry level outgrows `i64`.
+    Checked,
+    /// Lets worry levels wrap around, which is only right as long as they don't overflow.
+    Wrapping,
+}
+
+impl Default for Arithmetic {
+    /// Checked in debug builds, wrapping in release builds.
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            Arithmetic::Checked
+        } else {
+            Arithmetic::Wrapping
+        }
+    }
 }
 
-fn part1(input: &str) -> i64 {
+#[cfg(test)]
+fn part1(input: &str) -> usize {
+    part1_with(input, Arithmetic::default())
+}
+
+fn part1_with(input: &str, arithmetic: Arithmetic) -> usize {
     let monkeys = parse_input(input);
-    monkey_business::<20, _>(monkeys, |w| w / 3)
+    match arithmetic {
+        Arithmetic::Checked => {
+            let mut simulation = Simulation::new(monkeys, (), Some(BORED));
+            simulation.run(20);
+            simulation.monkey_business()
+        }
+        Arithmetic::Wrapping => {
+            let monkeys = monkeys
+                .into_iter()
+                .map(|monkey| monkey.map_items(Wrapping))
+                .collect();
+            let mut simulation = Simulation::new(monkeys, (), Some(BORED_WRAPPING));
+            simulation.run(20);
+            simulation.monkey_business()
+        }
+    }
 }
 
 #[test]
 fn part1_example() {
-    assert_eq!(10605, part1(EXAMPLE))
+    assert_eq!(10605, part1(EXAMPLE));
+    assert_eq!(10605, part1_with(EXAMPLE, Arithmetic::Checked));
+    assert_eq!(10605, part1_with(EXAMPLE, Arithmetic::Wrapping));
 }
 
 #[ignore]
@@ -135,13 +317,38 @@ fn part1_verify() {
     assert_eq!(120384, part1(INPUT))
 }
 
-fn part2(input: &str) -> i64 {
+/// The divisors of the monkeys' tests, when their product doesn't fit in a `u64`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct ModulusOverflow(Vec<u64>);
+
+impl Display for ModulusOverflow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the product of the divisors {:?} overflows u64", self.0)
+    }
+}
+
+fn part2_simulation(input: &str) -> Result<Simulation<ModularInt>, ModulusOverflow> {
     let monkeys = parse_input(input);
-    let worry_modifier = monkeys
+    // Worry levels modulo the product of all divisors still pass the same divisibility tests.
+    let divisors = monkeys
         .iter()
-        .map(|i| i.is_divisible_by)
-        .product::<WorryLevel>();
-    monkey_business::<10_000, _>(monkeys, |w| w % worry_modifier)
+        .map(|m| m.is_divisible_by)
+        .collect::<Vec<_>>();
+    let modulus = divisors
+        .iter()
+        .try_fold(1u64, |product, &divisor| product.checked_mul(divisor))
+        .ok_or(ModulusOverflow(divisors))?;
+    let monkeys = monkeys
+        .into_iter()
+        .map(|monkey| monkey.map_items(|w| ModularInt::new(w, modulus)))
+        .collect();
+    Ok(Simulation::new(monkeys, modulus, None))
+}
+
+fn part2(input: &str) -> usize {
+    let mut simulation = part2_simulation(input).unwrap_or_else(|error| panic!("{}", error));
+    simulation.run(10_000);
+    simulation.monkey_business()
 }
 
 #[test]
@@ -154,3 +361,106 @@ fn part2_example() {
 fn part2_verify() {
     assert_eq!(32059801242, part2(INPUT))
 }
+
+/// Prints the first round of part 1 in detail, and the inspection counts of part 2 at the
+/// rounds the puzzle shows them.
+fn print_trace(input: &str) {
+    let mut simulation = Simulation::new(parse_input(input), (), Some(BORED));
+    let mut log = String::new();
+    simulation.round(Some(&mut log));
+    println!("{}", log);
+    println!(
+        "After round 1, the monkeys are holding items with these worry levels:\n{}",
+        simulation.holdings()
+    );
+
+    let mut simulation = part2_simulation(input).unwrap_or_else(|error| panic!("{}", error));
+    for round in [
+        1, 20, 1000, 2000, 3000, 4000, 5000, 6000, 7000, 8000, 9000, 10_000,
+    ] {
+        simulation.run(round - simulation.rounds);
+        println!("{}", simulation.inspection_table());
+    }
+}
+
+#[test]
+fn trace_example() {
+    let mut simulation = Simulation::new(parse_input(EXAMPLE), (), Some(BORED));
+    let mut log = String::new();
+    simulation.round(Some(&mut log));
+    let expected = "\
+Monkey 0:
+  Monkey inspects an item with a worry level of 79.
+    Worry level is multiplied by 19 to 1501.
+    Monkey gets bored with item. Worry level is divided by 3 to 500.
+    Current worry level is not divisible by 23.
+    Item with worry level 500 is thrown to monkey 3.
+  Monkey inspects an item with a worry level of 98.
+    Worry level is multiplied by 19 to 1862.
+    Monkey gets bored with item. Worry level is divided by 3 to 620.
+    Current worry level is not divisible by 23.
+    Item with worry level 620 is thrown to monkey 3.
+Monkey 1:
+  Monkey inspects an item with a worry level of 54.
+    Worry level increases by 6 to 60.
+    Monkey gets bored with item. Worry level is divided by 3 to 20.
+    Current worry level is not divisible by 19.
+    Item with worry level 20 is thrown to monkey 0.
+";
+    assert!(log.starts_with(expected), "{}", log);
+    assert!(log.contains(
+        "\
+  Monkey inspects an item with a worry level of 79.
+    Worry level is multiplied by itself to 6241.
+    Monkey gets bored with item. Worry level is divided by 3 to 2080.
+    Current worry level is divisible by 13.
+    Item with worry level 2080 is thrown to monkey 1.
+"
+    ));
+    assert_eq!(
+        "Monkey 0: 20, 23, 27, 26\nMonkey 1: 2080, 25, 167, 207, 401, 1046\nMonkey 2: \nMonkey 3: \n",
+        simulation.holdings()
+    );
+
+    let mut simulation = part2_simulation(EXAMPLE).unwrap();
+    simulation.run(1000);
+    assert_eq!(
+        "\
+== After round 1000 ==
+Monkey 0 inspected items 5204 times.
+Monkey 1 inspected items 4792 times.
+Monkey 2 inspected items 199 times.
+Monkey 3 inspected items 5192 times.
+",
+        simulation.inspection_table()
+    );
+}
+
+#[test]
+#[should_panic(expected = "arithmetic overflow in new = old * old with old = 3037000500")]
+fn part1_overflow() {
+    let input = EXAMPLE.replace("Starting items: 79, 60, 97", "Starting items: 3037000500");
+    part1_with(&input, Arithmetic::Checked);
+}
+
+#[test]
+fn part2_modulus_overflow() {
+    let input = EXAMPLE
+        .replace("divisible by 23", "divisible by 4294967311")
+        .replace("divisible by 19", "divisible by 4294967357");
+    assert_eq!(
+        Some(ModulusOverflow(vec![4294967311, 4294967357, 13, 17])),
+        part2_simulation(&input).err()
+    );
+}
+
+#[test]
+fn inspect_modular_division() {
+    let input = EXAMPLE.replace("new = old * 19", "new = old * (7 / 2)");
+    let simulation = part2_simulation(&input).unwrap();
+    let modulus = simulation.context;
+    assert_eq!(
+        ModularInt::new(7, modulus),
+        simulation.monkeys[0].inspect(&modulus, ModularInt::new(2, modulus))
+    );
+}
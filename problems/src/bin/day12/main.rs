@@ -77,9 +77,7 @@ fn part1(input: &str) -> i32 {
 fn part2(input: &str) -> i32 {
     let (_, goal, heights) = parse_input(input);
     let starts = heights
-        .bounds
-        .iter_points()
-        .filter(|p| heights[*p] == 0)
+        .points_where(|height| *height == 0)
         .collect::<Vec<_>>();
     find_best_trail(&starts, goal, &heights)
 }
@@ -1,6 +1,8 @@
 #![feature(array_windows)]
 
+use adventofcode2022_common::ansi::LiveView;
 use adventofcode2022_common::charcanvas::CharCanvas;
+use adventofcode2022_common::image::Rgb;
 use adventofcode2022_common::vec2i::{Point, DOWN, DOWN_LEFT, DOWN_RIGHT};
 
 const INPUT: &str = include_str!("input.txt");
@@ -8,8 +10,9 @@ const INPUT: &str = include_str!("input.txt");
 const EXAMPLE: &str = include_str!("example.txt");
 
 fn main() {
-    eprintln!("part1 {:?}", part1(INPUT));
-    eprintln!("part2 {:?}", part2(INPUT));
+    let mut live = LiveView::from_args().every(20);
+    eprintln!("part1 {:?}", part1_with(INPUT, &mut live));
+    eprintln!("part2 {:?}", part2_with(INPUT, &mut live));
 }
 
 fn parse_input(input: &str) -> impl Iterator<Item = Vec<Point>> + '_ {
@@ -72,29 +75,54 @@ fn drop_sand(canvas: &mut CharCanvas, mut p: Point) -> SandPosition {
 
 const SAND_INGRESS: Point = Point::new(500, 0);
 
+fn tile_color(tile: char) -> Option<Rgb> {
+    match tile {
+        '#' => Some([128, 128, 128]),
+        'o' => Some([230, 190, 80]),
+        _ => None,
+    }
+}
+
 #[inline]
-fn simulate(mut canvas: CharCanvas) -> impl Iterator<Item = SandPosition> {
+fn simulate(
+    mut canvas: CharCanvas,
+    live: &mut LiveView,
+) -> impl Iterator<Item = SandPosition> + '_ {
     std::iter::from_fn(move || {
-        (canvas[SAND_INGRESS] != 'o').then(|| drop_sand(&mut canvas, SAND_INGRESS))
+        (canvas[SAND_INGRESS] != 'o').then(|| {
+            let sand_position = drop_sand(&mut canvas, SAND_INGRESS);
+            live.draw(&canvas, tile_color);
+            sand_position
+        })
     })
 }
 
+#[cfg(test)]
 fn part1(input: &str) -> usize {
+    part1_with(input, &mut LiveView::default())
+}
+
+fn part1_with(input: &str, live: &mut LiveView) -> usize {
     let paths = parse_input(input);
     let canvas = render_grid(paths);
 
-    simulate(canvas)
+    simulate(canvas, live)
         .take_while(|sp| matches!(sp, SandPosition::Resting))
         .count()
 }
 
+#[cfg(test)]
 fn part2(input: &str) -> usize {
+    part2_with(input, &mut LiveView::default())
+}
+
+fn part2_with(input: &str, live: &mut LiveView) -> usize {
     let paths = parse_input(input);
     let mut canvas = render_grid(paths);
 
     canvas.extend_to_point(&(canvas.bounds.bottom_right() + [0, 1]));
 
-    simulate(canvas).count()
+    simulate(canvas, live).count()
 }
 
 #[test]
@@ -1,20 +1,37 @@
-#![feature(iter_array_chunks)]
-#![feature(const_cmp, const_btree_len)]
-
-use adventofcode2022_common::vec2i::Point;
+use adventofcode2022_common::charcanvas::CharCanvas;
+use adventofcode2022_common::diamond::Diamond;
+use adventofcode2022_common::image::{ColorMap, Image, Rgb, BLACK};
+use adventofcode2022_common::intervals::IntervalSet;
+use adventofcode2022_common::parallel::Parallel;
+use adventofcode2022_common::rotation::Rotate45;
+use adventofcode2022_common::vec2i::{Bounds, Point};
 use std::cmp::{max, min};
 use std::collections::BTreeSet;
-use std::ops::RangeInclusive;
+use std::time::Instant;
 
 const SIDE: i32 = 4_000_000;
 
+const RED: Rgb = [224, 32, 32];
+const BLUE: Rgb = [64, 96, 255];
+const GREEN: Rgb = [32, 224, 32];
+
 const INPUT: &str = include_str!("input.txt");
-#[cfg(test)]
 const EXAMPLE: &str = include_str!("example.txt");
 
 fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.iter().any(|arg| arg == "--example") {
+        print_example();
+    }
+    if args.iter().any(|arg| arg == "--compare") {
+        compare_strategies(INPUT);
+    }
     eprintln!("part1 {:?}", part1(2000000, INPUT));
     eprintln!("part2 {:?}", part2(SIDE, SIDE, INPUT));
+    if let Some(path) = args.iter().skip_while(|arg| *arg != "--image").nth(1) {
+        let area = Bounds::new(0, SIDE, SIDE, 0);
+        render_coverage_image(INPUT, &area, 800).save(path).unwrap();
+    }
 }
 
 fn parse_input(input: &str) -> impl Iterator<Item = (Point, Point)> + '_ {
@@ -33,175 +50,259 @@ fn parse_input(input: &str) -> impl Iterator<Item = (Point, Point)> + '_ {
     input.lines().map(parse_line)
 }
 
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
-enum RangeBound {
-    Lower,
-    Upper,
+const fn tuning_frequency(x: i32, y: i32) -> usize {
+    x as usize * SIDE as usize + y as usize
 }
 
-#[derive(Default)]
-struct RangeSet {
-    ranges: BTreeSet<(i32, RangeBound)>,
+fn sensor_coverage_at_line(line: i32, sensors: &[Diamond]) -> IntervalSet<i32> {
+    sensors
+        .iter()
+        .filter_map(|sensor| sensor.row(line))
+        .collect()
 }
 
-impl RangeSet {
-    fn remove_inner(&mut self, start: i32, end: i32) {
-        let to_remove = self
-            .ranges
-            .range((start - 1, RangeBound::Upper)..=(end + 1, RangeBound::Lower))
-            .copied()
-            .collect::<Vec<_>>();
+fn part1(y: i32, input: &str) -> usize {
+    let mut beacons_at_line = BTreeSet::<i32>::new();
+    let mut sensors = Vec::new();
 
-        for r in &to_remove {
-            self.ranges.remove(r);
+    for (sensor, beacon) in parse_input(input) {
+        sensors.push(Diamond::with_center_and_point(sensor, &beacon));
+        if y == beacon.y() {
+            beacons_at_line.insert(beacon.x());
         }
     }
 
-    #[inline]
-    fn insert(&mut self, range: RangeInclusive<i32>) {
-        let start = *range.start();
-        let end = *range.end();
+    let coverage = sensor_coverage_at_line(y, &sensors);
 
-        let range_open = matches!(
-            self.ranges.range(..=(start - 1, RangeBound::Lower)).last(),
-            Some((_, RangeBound::Lower))
-        );
-
-        let range_closed = matches!(
-            self.ranges.range((end + 1, RangeBound::Upper)..).next(),
-            Some((_, RangeBound::Upper))
-        );
+    let beacons = beacons_at_line
+        .into_iter()
+        .filter(|b| coverage.contains(*b))
+        .count();
 
-        self.remove_inner(start, end);
+    coverage.len() as usize - beacons
+}
 
-        if !range_open {
-            self.ranges.insert((start, RangeBound::Lower));
-        }
+/// How part 2 searches for the one position no sensor covers.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Strategy {
+    /// Builds the coverage of every row until one has a gap.
+    RowScan,
+    /// Scans the rows on all cores.
+    ParallelRowScan,
+    /// Checks every point just outside a sensor's diamond.
+    Perimeter,
+    /// Checks where the edge lines just outside the diamonds cross each other or the borders
+    /// of the search area.
+    EdgeIntersections,
+}
 
-        if !range_closed {
-            self.ranges.insert((end, RangeBound::Upper));
+impl Strategy {
+    const ALL: [Strategy; 4] = [
+        Strategy::RowScan,
+        Strategy::ParallelRowScan,
+        Strategy::Perimeter,
+        Strategy::EdgeIntersections,
+    ];
+
+    fn find_uncovered(&self, sensors: &[Diamond], area: &Bounds) -> Option<Point> {
+        match self {
+            Strategy::RowScan => find_by_row_scan(sensors, area),
+            Strategy::ParallelRowScan => find_by_parallel_row_scan(sensors, area),
+            Strategy::Perimeter => find_on_perimeters(sensors, area),
+            Strategy::EdgeIntersections => find_at_edge_intersections(sensors, area),
         }
     }
+}
 
-    #[inline]
-    const fn len(&self) -> usize {
-        self.ranges.len() / 2
-    }
+fn is_uncovered(p: &Point, sensors: &[Diamond]) -> bool {
+    sensors.iter().all(|sensor| !sensor.contains(p))
+}
 
-    #[inline]
-    fn contains(&self, p: i32) -> bool {
-        matches!(
-            self.ranges.range((p, RangeBound::Upper)..).next(),
-            Some((_, RangeBound::Upper))
-        )
-    }
+fn find_in_row(y: i32, sensors: &[Diamond], area: &Bounds) -> Option<Point> {
+    let uncovered = sensor_coverage_at_line(y, sensors).complement(area.left()..=area.right());
+    uncovered.first().map(|x| Point::new(x, y))
+}
 
-    fn into_iter(self) -> impl Iterator<Item = RangeInclusive<i32>> {
-        self.ranges
-            .into_iter()
-            .array_chunks::<2>()
-            .map(|[(start, _), (end, _)]| start..=end)
-    }
+fn find_by_row_scan(sensors: &[Diamond], area: &Bounds) -> Option<Point> {
+    (area.top()..=area.bottom()).find_map(|y| find_in_row(y, sensors, area))
 }
 
-struct SensorCoverage {
-    x: i32,
-    y: i32,
-    range: i32,
+fn find_by_parallel_row_scan(sensors: &[Diamond], area: &Bounds) -> Option<Point> {
+    let rows = area.size().height() as usize;
+    Parallel::new().find_map(0..rows, |i| {
+        find_in_row(area.top() + i as i32, sensors, area)
+    })
 }
 
-impl SensorCoverage {
-    fn new(sensor: Point, beacon: Point) -> Self {
-        Self {
-            range: sensor.manhattan_distance(&beacon),
-            x: sensor.x(),
-            y: sensor.y(),
-        }
-    }
-    fn range_at_line(&self, y: i32) -> Option<RangeInclusive<i32>> {
-        let y_distance = self.y.abs_diff(y) as i32;
-        if y_distance > self.range {
-            return None;
-        }
-        let x_range = self.range - y_distance;
-        let x1 = self.x - x_range;
-        let x2 = self.x + x_range;
-        Some(x1..=x2)
-    }
+/// Any uncovered point next to a covered one is just outside the diamond covering it.
+fn find_on_perimeters(sensors: &[Diamond], area: &Bounds) -> Option<Point> {
+    sensors
+        .iter()
+        .flat_map(|sensor| sensor.outer_perimeter())
+        .find(|p| area.contains(p) && is_uncovered(p, sensors))
 }
 
-#[inline]
-const fn clamp_range<const LOW: i32, const HIGH: i32>(
-    range: &RangeInclusive<i32>,
-) -> Option<RangeInclusive<i32>> {
-    let start = max(*range.start(), LOW);
-    let end = min(*range.end(), HIGH);
-    if start <= end {
-        Some(RangeInclusive::new(start, end))
-    } else {
-        None
+/// The uncovered point is boxed in by diamonds, so it usually sits where the edges just outside
+/// two of them cross, or where such an edge meets a border. In rotated coordinates those edges
+/// are the sides of squares, so crossings are pairs of one `u` and one `v` side. Falls back to
+/// the perimeters for the rare layouts that box the point in some other way.
+fn find_at_edge_intersections(sensors: &[Diamond], area: &Bounds) -> Option<Point> {
+    let mut us = BTreeSet::new();
+    let mut vs = BTreeSet::new();
+    for sensor in sensors {
+        let square = Diamond::new(sensor.center(), sensor.radius() + 1).rotated_bounds();
+        us.extend([square.left(), square.right()]);
+        vs.extend([square.top(), square.bottom()]);
     }
+
+    let crossings = us.iter().flat_map(|&u| {
+        vs.iter()
+            .filter_map(move |&v| Rotate45.inverse(&Point::new(u, v)))
+    });
+    let (left, top, right, bottom) = (area.left(), area.top(), area.right(), area.bottom());
+    let on_borders = us
+        .iter()
+        .flat_map(|&u| {
+            [
+                Point::new(left, u - left),
+                Point::new(right, u - right),
+                Point::new(u - top, top),
+                Point::new(u - bottom, bottom),
+            ]
+        })
+        .chain(vs.iter().flat_map(|&v| {
+            [
+                Point::new(left, left - v),
+                Point::new(right, right - v),
+                Point::new(top + v, top),
+                Point::new(bottom + v, bottom),
+            ]
+        }));
+    let corners = [
+        Point::new(left, top),
+        Point::new(right, top),
+        Point::new(right, bottom),
+        Point::new(left, bottom),
+    ];
+
+    crossings
+        .chain(on_borders)
+        .chain(corners)
+        .find(|p| area.contains(p) && is_uncovered(p, sensors))
+        .or_else(|| find_on_perimeters(sensors, area))
 }
 
-const fn tuning_frequency(x: i32, y: i32) -> usize {
-    x as usize * SIDE as usize + y as usize
+fn part2(width: i32, height: i32, input: &str) -> usize {
+    part2_with(Strategy::EdgeIntersections, width, height, input)
 }
 
-fn sensor_coverage_at_line<const LOW: i32, const HIGH: i32>(
-    line: i32,
-    sensors: &[SensorCoverage],
-) -> RangeSet {
-    let mut coverage = RangeSet::default();
-    for sensor_coverage in sensors {
-        if let Some(range) = sensor_coverage.range_at_line(line) {
-            if let Some(range) = clamp_range::<LOW, HIGH>(&range) {
-                coverage.insert(range)
-            }
-        }
+fn part2_with(strategy: Strategy, width: i32, height: i32, input: &str) -> usize {
+    let sensors = parse_input(input)
+        .map(|(sensor, beacon)| Diamond::with_center_and_point(sensor, &beacon))
+        .collect::<Vec<_>>();
+
+    let area = Bounds::new(0, width, height, 0);
+    let p = strategy.find_uncovered(&sensors, &area).unwrap();
+    tuning_frequency(p.x(), p.y())
+}
+
+fn compare_strategies(input: &str) {
+    for strategy in Strategy::ALL {
+        let start = Instant::now();
+        let result = part2_with(strategy, SIDE, SIDE, input);
+        eprintln!("{:?}: {} in {:?}", strategy, result, start.elapsed());
     }
-    coverage
 }
 
-fn part1(y: i32, input: &str) -> usize {
-    let mut beacons_at_line = BTreeSet::<i32>::new();
-    let mut sensors = Vec::new();
+fn parse_sensors(input: &str) -> Vec<(Diamond, Point)> {
+    parse_input(input)
+        .map(|(sensor, beacon)| (Diamond::with_center_and_point(sensor, &beacon), beacon))
+        .collect()
+}
 
-    for (sensor, beacon) in parse_input(input) {
-        sensors.push(SensorCoverage::new(sensor, beacon));
-        if y == beacon.y() {
-            beacons_at_line.insert(beacon.x());
+/// Draws the sensors, their beacons and the positions they cover within `bounds`, the way the
+/// puzzle pictures them.
+fn render_coverage(input: &str, bounds: Bounds) -> CharCanvas {
+    let sensors = parse_sensors(input);
+    let mut canvas = CharCanvas::with_bounds('.', bounds);
+    for (diamond, _) in &sensors {
+        let covered = diamond.bounds().intersection(&bounds);
+        for y in covered.top()..=covered.bottom() {
+            let row = diamond.row(y).unwrap();
+            let left = max(*row.start(), bounds.left());
+            let right = min(*row.end(), bounds.right());
+            if left <= right {
+                canvas.line(&Point::new(left, y), &Point::new(right, y), '#');
+            }
         }
     }
+    for (diamond, beacon) in &sensors {
+        for (p, tile) in [(diamond.center(), 'S'), (*beacon, 'B')] {
+            if bounds.contains(&p) {
+                canvas[p] = tile;
+            }
+        }
+    }
+    canvas
+}
 
-    let coverage = sensor_coverage_at_line::<{ i32::MIN }, { i32::MAX }>(y, &sensors);
-
-    let beacons = beacons_at_line
-        .into_iter()
-        .filter(|b| coverage.contains(*b))
-        .count();
-
-    let coverage = coverage.into_iter().map(|r| r.count()).sum::<usize>();
-    coverage - beacons
+fn print_example() {
+    let bounds = parse_input(EXAMPLE)
+        .flat_map(|(sensor, beacon)| [sensor, beacon])
+        .fold(Bounds::EMPTY, |bounds, p| bounds.extend_to(&p));
+    let canvas = render_coverage(EXAMPLE, bounds);
+    println!("{}", canvas.renderer().axes(5));
 }
 
-fn part2(width: i32, height: i32, input: &str) -> usize {
-    let sensors = parse_input(input)
-        .map(|(sensor, beacon)| SensorCoverage::new(sensor, beacon))
+/// Draws `area` scaled down to `size` by `size` pixels. Each pixel is shaded by the number of
+/// sensors covering the position at its center, from black for none to white for the most.
+/// Sensors, beacons and the uncovered position are drawn on top as squares big enough to see.
+fn render_coverage_image(input: &str, area: &Bounds, size: usize) -> Image {
+    const MARKER: usize = 5;
+    let sensors = parse_sensors(input);
+    let diamonds = sensors
+        .iter()
+        .map(|(diamond, _)| *diamond)
         .collect::<Vec<_>>();
+    let [width, height]: [i32; 2] = area.size().into();
+    let to_position = |pixel: usize, start: i32, length: i32| {
+        start + ((2 * pixel + 1) as i64 * length as i64 / (2 * size) as i64) as i32
+    };
+    let to_pixel = |p: &Point| {
+        let offset = p.vector(&area.top_left());
+        (
+            (offset.x() as i64 * size as i64 / width as i64) as usize,
+            (offset.y() as i64 * size as i64 / height as i64) as usize,
+        )
+    };
+
+    let counts = (0..size)
+        .flat_map(|py| (0..size).map(move |px| (px, py)))
+        .map(|(px, py)| {
+            let p = Point::new(
+                to_position(px, area.left(), width),
+                to_position(py, area.top(), height),
+            );
+            diamonds.iter().filter(|d| d.contains(&p)).count() as u32
+        })
+        .collect::<Vec<_>>();
+    let shade = ColorMap::Grayscale.for_range(0, counts.iter().copied().max().unwrap_or(0));
+    let mut image = Image::new(size, size, BLACK);
+    for (i, count) in counts.iter().enumerate() {
+        image.set_pixel(i % size, i / size, shade(count));
+    }
 
-    for y in 0..height {
-        let coverage = sensor_coverage_at_line::<0, SIDE>(y, &sensors);
-
-        if coverage.len() > 1 {
-            let x = coverage.into_iter().next().unwrap().end() + 1;
-            return tuning_frequency(x, y);
-        } else if !coverage.contains(0) {
-            return tuning_frequency(0, y);
-        } else if !coverage.contains(width) {
-            return tuning_frequency(width, y);
-        }
+    let uncovered = Strategy::EdgeIntersections.find_uncovered(&diamonds, area);
+    let markers = sensors
+        .iter()
+        .flat_map(|(diamond, beacon)| [(diamond.center(), RED), (*beacon, BLUE)])
+        .chain(uncovered.map(|p| (p, GREEN)));
+    for (p, color) in markers.filter(|(p, _)| area.contains(p)) {
+        let (x, y) = to_pixel(&p);
+        let (x, y) = (x.saturating_sub(MARKER / 2), y.saturating_sub(MARKER / 2));
+        image.fill_rect(x, y, MARKER, MARKER, color);
     }
-    unreachable!()
+    image
 }
 
 #[test]
@@ -220,6 +321,79 @@ fn part2_example() {
     assert_eq!(56000011, part2(20, 20, EXAMPLE))
 }
 
+#[test]
+fn part2_strategies() {
+    for strategy in Strategy::ALL {
+        assert_eq!(
+            56000011,
+            part2_with(strategy, 20, 20, EXAMPLE),
+            "{:?}",
+            strategy
+        );
+    }
+    // Sensors near three corners of the area leave the fourth uncovered.
+    let corner = "\
+Sensor at x=4, y=0: closest beacon is at x=4, y=3
+Sensor at x=0, y=4: closest beacon is at x=3, y=4
+Sensor at x=4, y=4: closest beacon is at x=4, y=-2";
+    for strategy in Strategy::ALL {
+        assert_eq!(
+            tuning_frequency(0, 0),
+            part2_with(strategy, 4, 4, corner),
+            "{:?}",
+            strategy
+        );
+    }
+}
+
+#[test]
+fn render_example() {
+    let canvas = render_coverage(EXAMPLE, Bounds::new(0, 20, 20, 0));
+    assert_eq!(
+        "\
+##S##################
+####################S
+#############S#######
+##############SB#####
+#####################
+#####################
+#####################
+########S#######S####
+#####################
+#####################
+##B##################
+S#############.######
+#####################
+#####################
+############S#######S
+#####################
+#########SB##########
+##############S######
+##S##################
+#####################
+##########S######S###
+",
+        canvas.to_string()
+    );
+
+    let single = "Sensor at x=2, y=2: closest beacon is at x=3, y=3";
+    let canvas = render_coverage(single, Bounds::new(-1, 4, 4, 0));
+    assert_eq!(
+        ".....\n..#..\n.###.\n##S##\n.##B.\n..#..\n",
+        canvas.to_string()
+    );
+}
+
+#[test]
+fn render_example_image() {
+    let area = Bounds::new(0, 20, 20, 0);
+    let image = render_coverage_image(EXAMPLE, &area, 42);
+    assert_eq!((42, 42), (image.width(), image.height()));
+    assert_eq!(GREEN, image.pixel(14 * 2, 11 * 2));
+    assert_eq!(RED, image.pixel(8 * 2, 7 * 2));
+    assert_ne!(BLACK, image.pixel(0, 41));
+}
+
 #[ignore]
 #[test]
 fn part2_verify() {